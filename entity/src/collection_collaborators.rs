@@ -0,0 +1,74 @@
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::collections::{CollectionsLoader, UserId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Enum)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum CollaboratorRole {
+    /// may see the collection and its recipes
+    #[sea_orm(string_value = "viewer")]
+    Viewer,
+    /// may additionally change the collection and add, remove or reorder recipes
+    #[sea_orm(string_value = "editor")]
+    Editor,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "collection_collaborators")]
+#[graphql(complex, name = "CollectionCollaborator")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub collection_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    pub role: CollaboratorRole,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::collections::Entity",
+        from = "Column::CollectionId",
+        to = "super::collections::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Collections,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::collections::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Collections.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    /// the collaborator's name, only name and email are exposed to the other members
+    async fn name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.data_unchecked::<DataLoader<CollectionsLoader>>();
+        let user = loader.load_one(UserId(self.user_id)).await?;
+        Ok(user.and_then(|user| user.name))
+    }
+
+    async fn email(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.data_unchecked::<DataLoader<CollectionsLoader>>();
+        let user = loader.load_one(UserId(self.user_id)).await?;
+        Ok(user.map(|user| user.email))
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use itertools::Itertools;
+use sea_orm::QueryOrder;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::recipes::RecipeImage;
+use crate::{collection_collaborators, collections_recipes, recipes, users};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "collections")]
+#[graphql(complex, name = "Collection")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub owner_id: i64,
+    pub name: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+    #[graphql(skip)]
+    pub image: Option<String>,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::OwnerId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(has_many = "super::collections_recipes::Entity")]
+    CollectionsRecipes,
+    #[sea_orm(has_many = "super::collection_collaborators::Entity")]
+    CollectionCollaborators,
+}
+
+impl Related<super::collections_recipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CollectionsRecipes.def()
+    }
+}
+
+impl Related<super::collection_collaborators::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CollectionCollaborators.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn get_extension_from_filename(filename: &str) -> Option<&str> {
+    Path::new(filename).extension().and_then(OsStr::to_str)
+}
+
+#[ComplexObject]
+impl Model {
+    /// the recipes in the order given by the collection, private recipes of other users are left out
+    async fn recipes(&self, ctx: &Context<'_>) -> Result<Vec<recipes::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<CollectionsLoader>>();
+        let recipes: Option<Vec<recipes::Model>> = loader.load_one(RecipesId(self.id)).await?;
+        let user = ctx.data_opt::<users::Model>();
+
+        Ok(recipes
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|recipe| recipe.visible_to(user))
+            .collect())
+    }
+
+    async fn collaborators(&self, ctx: &Context<'_>) -> Result<Vec<collection_collaborators::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<CollectionsLoader>>();
+        let collaborators: Option<Vec<collection_collaborators::Model>> =
+            loader.load_one(CollaboratorsId(self.id)).await?;
+        Ok(collaborators.unwrap_or_default())
+    }
+
+    /// the cover image
+    async fn image(&self, _ctx: &Context<'_>) -> Option<RecipeImage> {
+        let ext = get_extension_from_filename(self.image.as_ref()?).unwrap_or("jpg");
+        let updated = self.updated_at.and_utc().timestamp();
+
+        Some(RecipeImage {
+            thumb: format!("/pictures/collections/{}/thumbnail.{}?{}", self.id, ext, updated),
+            large: format!("/pictures/collections/{}/large.{}?{}", self.id, ext, updated),
+            original: format!("/pictures/collections/{}/original.{}?{}", self.id, ext, updated),
+        })
+    }
+}
+
+pub struct CollectionsLoader {
+    pub conn: DatabaseConnection,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct RecipesId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct CollaboratorsId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub(crate) struct UserId(pub i64);
+
+impl Loader<RecipesId> for CollectionsLoader {
+    type Value = Vec<recipes::Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[RecipesId]) -> Result<HashMap<RecipesId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let recipes = collections_recipes::Entity::find()
+            .find_also_related(recipes::Entity)
+            .filter(collections_recipes::Column::CollectionId.is_in(ids))
+            .order_by_asc(collections_recipes::Column::CollectionId)
+            .order_by_asc(collections_recipes::Column::Position)
+            .all(&self.conn)
+            .await?;
+
+        let map = recipes
+            .into_iter()
+            .chunk_by(|(entry, _)| entry.collection_id)
+            .into_iter()
+            .map(|(key, group)| (RecipesId(key), group.filter_map(|(_, recipe)| recipe).collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+impl Loader<CollaboratorsId> for CollectionsLoader {
+    type Value = Vec<collection_collaborators::Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[CollaboratorsId]) -> Result<HashMap<CollaboratorsId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let collaborators = collection_collaborators::Entity::find()
+            .filter(collection_collaborators::Column::CollectionId.is_in(ids))
+            .order_by_asc(collection_collaborators::Column::CollectionId)
+            .order_by_asc(collection_collaborators::Column::InsertedAt)
+            .all(&self.conn)
+            .await?;
+
+        let map = collaborators
+            .into_iter()
+            .chunk_by(|collaborator| collaborator.collection_id)
+            .into_iter()
+            .map(|(key, group)| (CollaboratorsId(key), group.collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+impl Loader<UserId> for CollectionsLoader {
+    type Value = users::Model;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[UserId]) -> Result<HashMap<UserId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|id| id.0).collect_vec();
+
+        let users = users::Entity::find()
+            .filter(users::Column::Id.is_in(ids))
+            .all(&self.conn)
+            .await?;
+
+        let map = users.into_iter().map(|user| (UserId(user.id), user)).collect();
+
+        Ok(map)
+    }
+}
@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "collections_recipes")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub collection_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub recipe_id: i64,
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::collections::Entity",
+        from = "Column::CollectionId",
+        to = "super::collections::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Collections,
+    #[sea_orm(
+        belongs_to = "super::recipes::Entity",
+        from = "Column::RecipeId",
+        to = "super::recipes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Recipes,
+}
+
+impl Related<super::collections::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Collections.def()
+    }
+}
+
+impl Related<super::recipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Recipes.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
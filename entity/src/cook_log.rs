@@ -0,0 +1,68 @@
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "cook_log")]
+#[graphql(complex, name = "CookLogEntry")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub recipe_id: i64,
+    pub weekplan_id: Option<i64>,
+    pub date: Date,
+    /// 1 (didn't like it at all) to 5 (loved it)
+    pub rating: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub note: Option<String>,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::recipes::Entity",
+        from = "Column::RecipeId",
+        to = "super::recipes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Recipe,
+    #[sea_orm(
+        belongs_to = "super::weekplans::Entity",
+        from = "Column::WeekplanId",
+        to = "super::weekplans::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Weekplan,
+}
+
+impl Related<super::recipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Recipe.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    async fn recipe(&self, ctx: &Context<'_>) -> Result<Option<super::recipes::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<super::weekplans::WeekplanLoader>>();
+        let recipe: Option<super::recipes::Model> = loader.load_one(self.recipe_id).await?;
+
+        Ok(recipe)
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use itertools::Itertools;
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use crate::users;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "households")]
+#[graphql(complex, name = "Household")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    /// other users join the household with this code, empty until an invite has been created
+    #[sea_orm(unique)]
+    pub invite_code: Option<String>,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::users::Entity")]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// A member of a household, only name and email are exposed to the other members
+#[derive(Clone, Debug, SimpleObject)]
+pub struct HouseholdMember {
+    pub id: i64,
+    pub name: Option<String>,
+    pub email: String,
+}
+
+#[ComplexObject]
+impl Model {
+    async fn members(&self, ctx: &Context<'_>) -> Result<Vec<HouseholdMember>> {
+        let loader = ctx.data_unchecked::<DataLoader<HouseholdsLoader>>();
+        let members = loader.load_one(MembersId(self.id)).await?;
+
+        Ok(members
+            .unwrap_or_default()
+            .into_iter()
+            .map(|user| HouseholdMember {
+                id: user.id,
+                name: user.name,
+                email: user.email,
+            })
+            .collect())
+    }
+}
+
+pub struct HouseholdsLoader {
+    pub conn: DatabaseConnection,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct MembersId(i64);
+
+impl Loader<MembersId> for HouseholdsLoader {
+    type Value = Vec<users::Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[MembersId]) -> Result<HashMap<MembersId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let users = users::Entity::find()
+            .filter(users::Column::HouseholdId.is_in(ids))
+            .order_by_asc(users::Column::HouseholdId)
+            .order_by_asc(users::Column::Id)
+            .all(&self.conn)
+            .await?;
+
+        let map = users
+            .into_iter()
+            .chunk_by(|user| user.household_id)
+            .into_iter()
+            .map(|(key, group)| (MembersId(key), group.collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
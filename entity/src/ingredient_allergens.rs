@@ -0,0 +1,70 @@
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The 14 allergens which have to be declared in the EU (Regulation (EU) No 1169/2011, Annex II)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Enum)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(32))")]
+pub enum Allergen {
+    /// cereals containing gluten: wheat, rye, barley, oats, spelt, kamut
+    #[sea_orm(string_value = "gluten")]
+    Gluten,
+    #[sea_orm(string_value = "crustaceans")]
+    Crustaceans,
+    #[sea_orm(string_value = "eggs")]
+    Eggs,
+    #[sea_orm(string_value = "fish")]
+    Fish,
+    #[sea_orm(string_value = "peanuts")]
+    Peanuts,
+    #[sea_orm(string_value = "soybeans")]
+    Soybeans,
+    /// milk including lactose
+    #[sea_orm(string_value = "milk")]
+    Milk,
+    /// tree nuts: almonds, hazelnuts, walnuts, cashews, pecans, brazil nuts, pistachios, macadamia nuts
+    #[sea_orm(string_value = "nuts")]
+    Nuts,
+    #[sea_orm(string_value = "celery")]
+    Celery,
+    #[sea_orm(string_value = "mustard")]
+    Mustard,
+    #[sea_orm(string_value = "sesame")]
+    Sesame,
+    /// sulphur dioxide and sulphites above 10 mg/kg
+    #[sea_orm(string_value = "sulphites")]
+    Sulphites,
+    #[sea_orm(string_value = "lupin")]
+    Lupin,
+    #[sea_orm(string_value = "molluscs")]
+    Molluscs,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "ingredient_allergens")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub ingredient_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub allergen: Allergen,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ingredients::Entity",
+        from = "Column::IngredientId",
+        to = "super::ingredients::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Ingredients,
+}
+
+impl Related<super::ingredients::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ingredients.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
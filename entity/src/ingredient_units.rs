@@ -4,8 +4,10 @@ use async_graphql::*;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::ingredients::Reference;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Enum)]
-#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(2))")]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(255))")]
 pub enum Units {
     #[sea_orm(string_value = "pcs")]
     PCS,
@@ -17,11 +19,121 @@ pub enum Units {
     SKOSH,
     #[sea_orm(string_value = "pinch")]
     PINCH,
+    #[sea_orm(string_value = "g")]
+    G,
+    #[sea_orm(string_value = "kg")]
+    KG,
+    #[sea_orm(string_value = "ml")]
+    ML,
+    #[sea_orm(string_value = "cl")]
+    CL,
+    #[sea_orm(string_value = "dl")]
+    DL,
+    #[sea_orm(string_value = "l")]
+    L,
+    #[sea_orm(string_value = "cup")]
+    CUP,
+    #[sea_orm(string_value = "can")]
+    CAN,
+    #[sea_orm(string_value = "packet")]
+    PACKET,
+    #[sea_orm(string_value = "bunch")]
+    BUNCH,
+    #[sea_orm(string_value = "clove")]
+    CLOVE,
+    #[sea_orm(string_value = "slice")]
+    SLICE,
+    #[sea_orm(string_value = "sprig")]
+    SPRIG,
+    #[sea_orm(string_value = "handful")]
+    HANDFUL,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Enum)]
+pub enum Language {
+    #[default]
+    De,
+    En,
+}
+
+impl Units {
+    pub fn display_name(&self, language: Language) -> &'static str {
+        match language {
+            Language::De => match self {
+                Units::PCS => "Stück",
+                Units::TBSP => "Esslöffel",
+                Units::TSP => "Teelöffel",
+                Units::SKOSH => "Prise",
+                Units::PINCH => "Messerspitze",
+                Units::G => "Gramm",
+                Units::KG => "Kilogramm",
+                Units::ML => "Milliliter",
+                Units::CL => "Zentiliter",
+                Units::DL => "Deziliter",
+                Units::L => "Liter",
+                Units::CUP => "Tasse",
+                Units::CAN => "Dose",
+                Units::PACKET => "Packung",
+                Units::BUNCH => "Bund",
+                Units::CLOVE => "Zehe",
+                Units::SLICE => "Scheibe",
+                Units::SPRIG => "Zweig",
+                Units::HANDFUL => "Handvoll",
+            },
+            Language::En => match self {
+                Units::PCS => "piece",
+                Units::TBSP => "tablespoon",
+                Units::TSP => "teaspoon",
+                Units::SKOSH => "pinch",
+                Units::PINCH => "knife tip",
+                Units::G => "gram",
+                Units::KG => "kilogram",
+                Units::ML => "millilitre",
+                Units::CL => "centilitre",
+                Units::DL => "decilitre",
+                Units::L => "litre",
+                Units::CUP => "cup",
+                Units::CAN => "can",
+                Units::PACKET => "packet",
+                Units::BUNCH => "bunch",
+                Units::CLOVE => "clove",
+                Units::SLICE => "slice",
+                Units::SPRIG => "sprig",
+                Units::HANDFUL => "handful",
+            },
+        }
+    }
+
+    /// The fixed size of a unit in g or ml. Units like pieces, cans or bunches depend on the ingredient and have no
+    /// standard size.
+    pub fn standard_measure(&self) -> Option<(Reference, f64)> {
+        match self {
+            Units::G => Some((Reference::G, 1.0)),
+            Units::KG => Some((Reference::G, 1000.0)),
+            Units::ML => Some((Reference::ML, 1.0)),
+            Units::CL => Some((Reference::ML, 10.0)),
+            Units::DL => Some((Reference::ML, 100.0)),
+            Units::L => Some((Reference::ML, 1000.0)),
+            Units::CUP => Some((Reference::ML, 250.0)),
+            Units::TBSP => Some((Reference::ML, 15.0)),
+            Units::TSP => Some((Reference::ML, 5.0)),
+            Units::SKOSH => Some((Reference::G, 0.5)),
+            Units::PINCH => Some((Reference::G, 0.25)),
+            Units::PCS
+            | Units::CAN
+            | Units::PACKET
+            | Units::BUNCH
+            | Units::CLOVE
+            | Units::SLICE
+            | Units::SPRIG
+            | Units::HANDFUL => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "ingredient_units")]
-#[graphql(concrete(name = "IngredientUnit", params()))]
+#[graphql(complex, concrete(name = "IngredientUnit", params()))]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
@@ -59,3 +171,10 @@ impl Related<super::steps_ingredients::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    async fn name(&self, #[graphql(default)] language: Language) -> &'static str {
+        self.identifier.display_name(language)
+    }
+}
@@ -8,6 +8,7 @@ use itertools::Itertools;
 use sea_orm::{QueryOrder, entity::prelude::*};
 use serde::{Deserialize, Serialize};
 
+use crate::ingredient_allergens::{self, Allergen};
 use crate::ingredient_units;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Enum)]
@@ -19,6 +20,29 @@ pub enum Reference {
     ML,
 }
 
+/// Diets a recipe is suitable for when all of its ingredients are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum Diet {
+    Vegan,
+    Vegetarian,
+    GlutenFree,
+    LactoseFree,
+}
+
+impl Diet {
+    pub const ALL: [Diet; 4] = [Diet::Vegan, Diet::Vegetarian, Diet::GlutenFree, Diet::LactoseFree];
+
+    /// The column flagging ingredients suitable for the diet
+    pub fn column(&self) -> Column {
+        match self {
+            Diet::Vegan => Column::Vegan,
+            Diet::Vegetarian => Column::Vegetarian,
+            Diet::GlutenFree => Column::GlutenFree,
+            Diet::LactoseFree => Column::LactoseFree,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "ingredients")]
 #[graphql(complex, concrete(name = "Ingredient", params()))]
@@ -31,18 +55,39 @@ pub struct Model {
     pub fat: f64,
     pub proteins: f64,
     pub alc: f64,
+    /// dietary fibre, not included in `carbs`
+    pub fibre: f64,
+    /// sugars, included in `carbs`
+    pub sugars: f64,
+    /// saturated fatty acids, included in `fat`
+    pub saturated_fat: f64,
+    pub salt: f64,
+    pub vegan: bool,
+    pub vegetarian: bool,
+    pub gluten_free: bool,
+    pub lactose_free: bool,
+    /// grams per millilitre, needed to convert between g and ml
+    pub density: Option<f64>,
     pub inserted_at: DateTime,
     pub updated_at: DateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::ingredient_allergens::Entity")]
+    IngredientAllergens,
     #[sea_orm(has_many = "super::ingredient_units::Entity")]
     IngredientUnits,
     #[sea_orm(has_many = "super::steps_ingredients::Entity")]
     StepsIngridients,
 }
 
+impl Related<super::ingredient_allergens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::IngredientAllergens.def()
+    }
+}
+
 impl Related<super::ingredient_units::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::IngredientUnits.def()
@@ -57,6 +102,15 @@ impl Related<super::steps_ingredients::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+/// Grams of salt per gram of sodium, as used for nutrition labels
+pub const SALT_PER_SODIUM: f64 = 2.5;
+
+/// Energy in kcal of the given grams of carbohydrates, fat, proteins, alcohol and fibre. Fibre counts with 2 kcal/g
+/// (Regulation (EU) No 1169/2011) and is not part of the carbohydrates.
+pub fn energy(carbs: f64, fat: f64, proteins: f64, alc: f64, fibre: f64) -> f64 {
+    carbs * 4.1 + fat * 9.3 + proteins * 4.1 + alc * 7.1 + fibre * 2.0
+}
+
 pub struct IngredientLoader {
     pub conn: DatabaseConnection,
 }
@@ -64,6 +118,9 @@ pub struct IngredientLoader {
 #[derive(Clone, Eq, PartialEq, Hash)]
 struct UnitId(pub i64);
 
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct AllergenId(pub i64);
+
 #[ComplexObject]
 impl Model {
     async fn units(&self, ctx: &Context<'_>) -> Result<Vec<ingredient_units::Model>> {
@@ -73,8 +130,20 @@ impl Model {
         Ok(units.unwrap_or_default())
     }
 
+    async fn allergens(&self, ctx: &Context<'_>) -> Result<Vec<Allergen>> {
+        let loader = ctx.data_unchecked::<DataLoader<IngredientLoader>>();
+        let allergens = loader.load_one(AllergenId(self.id)).await?;
+
+        Ok(allergens.unwrap_or_default())
+    }
+
     async fn calories(&self) -> f64 {
-        self.alc * 7.1 + self.carbs * 4.1 + self.fat * 9.3 + self.proteins * 4.1
+        energy(self.carbs, self.fat, self.proteins, self.alc, self.fibre)
+    }
+
+    /// sodium content derived from the salt content
+    async fn sodium(&self) -> f64 {
+        self.salt / SALT_PER_SODIUM
     }
 }
 
@@ -103,3 +172,28 @@ impl Loader<UnitId> for IngredientLoader {
         Ok(map)
     }
 }
+
+impl Loader<AllergenId> for IngredientLoader {
+    type Value = Vec<Allergen>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[AllergenId]) -> Result<HashMap<AllergenId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let allergens = ingredient_allergens::Entity::find()
+            .filter(ingredient_allergens::Column::IngredientId.is_in(ids))
+            .order_by_asc(ingredient_allergens::Column::IngredientId)
+            .order_by_asc(ingredient_allergens::Column::Allergen)
+            .all(&self.conn)
+            .await?;
+
+        let map = allergens
+            .into_iter()
+            .chunk_by(|allergen| allergen.ingredient_id)
+            .into_iter()
+            .map(|(key, group)| (AllergenId(key), group.map(|allergen| allergen.allergen).collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
@@ -1,10 +1,26 @@
+pub mod collection_collaborators;
+pub mod collections;
+pub mod collections_recipes;
+pub mod cook_log;
+pub mod favorites;
 pub mod fitting;
+pub mod households;
+pub mod ingredient_allergens;
 pub mod ingredient_units;
 pub mod ingredients;
+pub mod nutrition_targets;
+pub mod pantry_items;
+pub mod recipe_comments;
+pub mod recipe_revisions;
 pub mod recipes;
 pub mod recipes_tags;
+pub mod shopping_list_items;
+pub mod shopping_lists;
 pub mod steps;
 pub mod steps_ingredients;
 pub mod tags;
+pub mod unit_conversion;
 pub mod users;
+pub mod weekplan_template_entries;
+pub mod weekplan_templates;
 pub mod weekplans;
@@ -0,0 +1,38 @@
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A user's daily nutrition targets. Unset values have no target.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "nutrition_targets")]
+#[graphql(name = "NutritionTargets")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub user_id: i64,
+    /// kcal per day
+    pub calories: Option<f64>,
+    /// g per day
+    pub proteins: Option<f64>,
+    /// g per day
+    pub carbs: Option<f64>,
+    /// g per day
+    pub fats: Option<f64>,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
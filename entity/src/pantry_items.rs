@@ -0,0 +1,84 @@
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::shopping_lists::{IngredientId, ShoppingListLoader, UnitId};
+use crate::{ingredient_units, ingredients};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "pantry_items")]
+#[graphql(complex, name = "PantryItem")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub ingredient_id: i64,
+    /// the unit `amount` is given in, the ingredient's reference (g/ml) when empty
+    pub unit_id: Option<i64>,
+    pub amount: f64,
+    pub best_before: Option<Date>,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::ingredients::Entity",
+        from = "Column::IngredientId",
+        to = "super::ingredients::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Ingredients,
+    #[sea_orm(
+        belongs_to = "super::ingredient_units::Entity",
+        from = "Column::UnitId",
+        to = "super::ingredient_units::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    IngredientUnits,
+}
+
+impl Related<super::ingredients::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ingredients.def()
+    }
+}
+
+impl Related<super::ingredient_units::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::IngredientUnits.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    async fn ingredient(&self, ctx: &Context<'_>) -> Result<Option<ingredients::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<ShoppingListLoader>>();
+        let ingredient = loader.load_one(IngredientId(self.ingredient_id)).await?;
+        Ok(ingredient)
+    }
+
+    async fn unit(&self, ctx: &Context<'_>) -> Result<Option<ingredient_units::Model>> {
+        if let Some(unit_id) = self.unit_id {
+            let loader = ctx.data_unchecked::<DataLoader<ShoppingListLoader>>();
+            let unit = loader.load_one(UnitId(unit_id)).await?;
+            Ok(unit)
+        } else {
+            Ok(None)
+        }
+    }
+}
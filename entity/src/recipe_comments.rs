@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use itertools::Itertools;
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, FromQueryResult, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use crate::users;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "recipe_comments")]
+#[graphql(complex, name = "RecipeComment")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub recipe_id: i64,
+    pub author_id: i64,
+    /// the comment this one replies to
+    pub parent_id: Option<i64>,
+    #[sea_orm(column_type = "Text")]
+    pub body: String,
+    pub inserted_at: DateTime,
+    /// when the body was changed the last time, empty for unedited comments
+    pub edited_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::recipes::Entity",
+        from = "Column::RecipeId",
+        to = "super::recipes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Recipe,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::AuthorId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Author,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Parent,
+}
+
+impl Related<super::recipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Recipe.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    /// the author's name, the rest of the author's account is not exposed
+    async fn author_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.data_unchecked::<DataLoader<RecipeCommentsLoader>>();
+        let author = loader.load_one(AuthorId(self.author_id)).await?;
+        Ok(author.and_then(|author| author.name))
+    }
+
+    async fn reply_count(&self, ctx: &Context<'_>) -> Result<i64> {
+        let loader = ctx.data_unchecked::<DataLoader<RecipeCommentsLoader>>();
+        let count = loader.load_one(ReplyCountId(self.id)).await?;
+        Ok(count.unwrap_or(0))
+    }
+}
+
+pub struct RecipeCommentsLoader {
+    pub conn: DatabaseConnection,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct AuthorId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct ReplyCountId(i64);
+
+impl Loader<AuthorId> for RecipeCommentsLoader {
+    type Value = users::Model;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[AuthorId]) -> Result<HashMap<AuthorId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|id| id.0).collect_vec();
+
+        let users = users::Entity::find()
+            .filter(users::Column::Id.is_in(ids))
+            .all(&self.conn)
+            .await?;
+
+        let map = users.into_iter().map(|user| (AuthorId(user.id), user)).collect();
+
+        Ok(map)
+    }
+}
+
+#[derive(FromQueryResult)]
+struct ParentIdAndCount {
+    parent_id: i64,
+    count: i64,
+}
+
+impl Loader<ReplyCountId> for RecipeCommentsLoader {
+    type Value = i64;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[ReplyCountId]) -> Result<HashMap<ReplyCountId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let counts = Entity::find()
+            .select_only()
+            .column(Column::ParentId)
+            .column_as(Column::Id.count(), "count")
+            .filter(Column::ParentId.is_in(ids))
+            .group_by(Column::ParentId)
+            .into_model::<ParentIdAndCount>()
+            .all(&self.conn)
+            .await?;
+
+        Ok(counts
+            .into_iter()
+            .map(|count| (ReplyCountId(count.parent_id), count.count))
+            .collect())
+    }
+}
@@ -0,0 +1,82 @@
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The state of a recipe at some point in time, as stored in `recipe_revisions.snapshot`. The visibility is not part
+/// of it, restoring an old revision must not publish a recipe made private since.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct RecipeSnapshot {
+    pub name: String,
+    pub default_servings: i32,
+    pub description: Option<String>,
+    /// sorted by id
+    pub tag_ids: Vec<i64>,
+    /// sorted by position
+    pub steps: Vec<StepSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct StepSnapshot {
+    pub name: Option<String>,
+    pub position: i32,
+    pub description: Option<String>,
+    pub preparation_time: i32,
+    pub cooking_time: i32,
+    pub ingredients: Vec<StepIngredientSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct StepIngredientSnapshot {
+    pub ingredient_id: i64,
+    pub amount: Option<f64>,
+    pub annotation: Option<String>,
+    pub unit_id: Option<i64>,
+}
+
+/// A snapshot of a recipe taken right before it was changed
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "recipe_revisions")]
+#[graphql(complex, name = "RecipeRevision")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub recipe_id: i64,
+    #[sea_orm(column_type = "JsonBinary")]
+    #[graphql(skip)]
+    pub snapshot: serde_json::Value,
+    pub inserted_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::recipes::Entity",
+        from = "Column::RecipeId",
+        to = "super::recipes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Recipes,
+}
+
+impl Related<super::recipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Recipes.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn recipe_snapshot(&self) -> Result<RecipeSnapshot, DbErr> {
+        serde_json::from_value(self.snapshot.clone())
+            .map_err(|e| DbErr::Json(format!("Invalid snapshot in revision {}: {}", self.id, e)))
+    }
+}
+
+#[ComplexObject]
+impl Model {
+    async fn snapshot(&self) -> Result<RecipeSnapshot> {
+        self.recipe_snapshot().map_err(|e| e.into())
+    }
+}
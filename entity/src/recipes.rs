@@ -12,7 +12,27 @@ use sea_orm::entity::prelude::*;
 use sea_orm::{DatabaseConnection, FromQueryResult, JoinType, QueryOrder, QuerySelect};
 use serde::{Deserialize, Serialize};
 
-use crate::{fitting, ingredient_units, ingredients, recipes_tags, steps, steps_ingredients, tags};
+use crate::ingredient_allergens::{self, Allergen};
+use crate::ingredients::Diet;
+use crate::{
+    cook_log, favorites, fitting, ingredient_units, ingredients, recipe_comments, recipe_revisions, recipes_tags,
+    steps, steps_ingredients, tags, unit_conversion, users,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Enum)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum Visibility {
+    /// listed and visible for everyone
+    #[default]
+    #[sea_orm(string_value = "public")]
+    Public,
+    /// visible for everyone knowing the link, but only listed for the owner
+    #[sea_orm(string_value = "unlisted")]
+    Unlisted,
+    /// only visible for the owner
+    #[sea_orm(string_value = "private")]
+    Private,
+}
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "recipes")]
@@ -29,6 +49,9 @@ pub struct Model {
     pub updated_at: DateTime,
     #[graphql(skip)]
     pub image: Option<String>,
+    pub visibility: Visibility,
+    /// the recipe this one was forked from
+    pub forked_from_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -90,9 +113,28 @@ struct TagId(pub i64);
 struct StepId(i64);
 #[derive(Clone, Eq, PartialEq, Hash)]
 struct FittingRecipesId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct RevisionsId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct CommentCountId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct ForkedFromId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct VariantsId(i64);
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 struct CaloriesId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct AllergensId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct DietsId(i64);
+
+/// recipe id and user id
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct CookStatsId(i64, i64);
+/// recipe id and user id
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct FavoriteId(i64, i64);
 
 #[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
 pub struct RecipeImage {
@@ -105,13 +147,69 @@ fn get_extension_from_filename(filename: &str) -> Option<&str> {
     Path::new(filename).extension().and_then(OsStr::to_str)
 }
 
-#[derive(Clone, Debug, Serialize, SimpleObject)]
-struct CaloriesResult {
-    proteins: f64,
-    carbs: f64,
-    fats: f64,
-    alcohol: f64,
-    calories: f64,
+#[derive(Clone, Debug, Default, Serialize, SimpleObject)]
+pub struct CaloriesResult {
+    pub proteins: f64,
+    pub carbs: f64,
+    pub fats: f64,
+    pub alcohol: f64,
+    pub fibre: f64,
+    pub sugars: f64,
+    pub saturated_fat: f64,
+    pub salt: f64,
+    pub calories: f64,
+}
+
+impl CaloriesResult {
+    pub fn scale(&self, factor: f64) -> Self {
+        Self {
+            proteins: self.proteins * factor,
+            carbs: self.carbs * factor,
+            fats: self.fats * factor,
+            alcohol: self.alcohol * factor,
+            fibre: self.fibre * factor,
+            sugars: self.sugars * factor,
+            saturated_fat: self.saturated_fat * factor,
+            salt: self.salt * factor,
+            calories: self.calories * factor,
+        }
+    }
+
+    pub fn add(&mut self, other: &CaloriesResult) {
+        self.proteins += other.proteins;
+        self.carbs += other.carbs;
+        self.fats += other.fats;
+        self.alcohol += other.alcohol;
+        self.fibre += other.fibre;
+        self.sugars += other.sugars;
+        self.saturated_fat += other.saturated_fat;
+        self.salt += other.salt;
+        self.calories += other.calories;
+    }
+}
+
+impl Model {
+    /// URLs of the recipe's picture in all sizes, relative to the server root
+    pub fn image_urls(&self) -> Option<RecipeImage> {
+        let ext = get_extension_from_filename(self.image.as_ref()?).unwrap_or("jpg");
+        let updated = self.updated_at.and_utc().timestamp();
+
+        Some(RecipeImage {
+            thumb: format!("/pictures/{}/thumbnail.{}?{}", self.id, ext, updated),
+            large: format!("/pictures/{}/large.{}?{}", self.id, ext, updated),
+            original: format!("/pictures/{}/original.{}?{}", self.id, ext, updated),
+        })
+    }
+
+    /// Private recipes are only visible for their owner and root users; unlisted recipes are visible for everyone
+    /// knowing the id, they are only left out of lists
+    pub fn visible_to(&self, user: Option<&users::Model>) -> bool {
+        if self.visibility != Visibility::Private {
+            return true;
+        }
+
+        user.is_some_and(|user| user.role == users::Role::Root || self.owner_id == Some(user.id))
+    }
 }
 
 #[ComplexObject]
@@ -128,29 +226,125 @@ impl Model {
         Ok(steps.unwrap_or_default())
     }
 
+    /// snapshots of the recipe taken before each change, newest first
+    async fn revisions(&self, ctx: &Context<'_>) -> Result<Vec<recipe_revisions::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let revisions: Option<Vec<recipe_revisions::Model>> = loader.load_one(RevisionsId(self.id)).await?;
+        Ok(revisions.unwrap_or_default())
+    }
+
+    /// the number of comments including replies
+    async fn comment_count(&self, ctx: &Context<'_>) -> Result<i64> {
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let count = loader.load_one(CommentCountId(self.id)).await?;
+        Ok(count.unwrap_or(0))
+    }
+
     async fn fitting_recipes(&self, ctx: &Context<'_>) -> Result<Vec<Model>> {
         let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
         let fitting_recipes: Option<Vec<Model>> = loader.load_one(FittingRecipesId(self.id)).await?;
-        Ok(fitting_recipes.unwrap_or_default())
+        let user = ctx.data_opt::<users::Model>();
+
+        Ok(fitting_recipes
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|recipe| recipe.visible_to(user))
+            .collect())
     }
 
-    async fn image(&self, _ctx: &Context<'_>) -> Option<RecipeImage> {
-        let ext = get_extension_from_filename(self.image.as_ref()?).or(Some("jpg"))?;
-        let updated = self.updated_at.and_utc().timestamp();
+    /// the recipe this one was forked from, if it still exists and is visible
+    async fn forked_from(&self, ctx: &Context<'_>) -> Result<Option<Model>> {
+        let Some(forked_from_id) = self.forked_from_id else {
+            return Ok(None);
+        };
 
-        self.image.as_ref().map(|_| RecipeImage {
-            thumb: format!("/pictures/{}/thumbnail.{}?{}", self.id, ext, updated),
-            large: format!("/pictures/{}/large.{}?{}", self.id, ext, updated),
-            original: format!("/pictures/{}/original.{}?{}", self.id, ext, updated),
-        })
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let recipe: Option<Model> = loader.load_one(ForkedFromId(forked_from_id)).await?;
+        let user = ctx.data_opt::<users::Model>();
+
+        Ok(recipe.filter(|recipe| recipe.visible_to(user)))
     }
 
+    /// the visible recipes forked from this one
+    async fn variants(&self, ctx: &Context<'_>) -> Result<Vec<Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let variants: Option<Vec<Model>> = loader.load_one(VariantsId(self.id)).await?;
+        let user = ctx.data_opt::<users::Model>();
+
+        Ok(variants
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|recipe| recipe.visible_to(user))
+            .collect())
+    }
+
+    async fn image(&self, _ctx: &Context<'_>) -> Option<RecipeImage> {
+        self.image_urls()
+    }
+
+    /// the nutrition values of one serving, step ingredient amounts are given per serving
     async fn calories(&self, ctx: &Context<'_>) -> Result<Option<CaloriesResult>> {
         let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
         let calories = loader.load_one(CaloriesId(self.id)).await?;
 
         Ok(calories)
     }
+
+    /// allergens contained in any of the recipe's ingredients
+    async fn allergens(&self, ctx: &Context<'_>) -> Result<Vec<Allergen>> {
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let allergens = loader.load_one(AllergensId(self.id)).await?;
+
+        Ok(allergens.unwrap_or_default())
+    }
+
+    /// diets all of the recipe's ingredients are suitable for
+    async fn diets(&self, ctx: &Context<'_>) -> Result<Vec<Diet>> {
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let diets = loader.load_one(DietsId(self.id)).await?;
+
+        Ok(diets.unwrap_or_else(|| Diet::ALL.to_vec()))
+    }
+
+    /// average rating of the current user's cook log entries
+    async fn average_rating(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        Ok(self.cook_stats(ctx).await?.and_then(|stats| stats.average_rating))
+    }
+
+    /// the last day the current user cooked the recipe
+    async fn last_cooked_at(&self, ctx: &Context<'_>) -> Result<Option<Date>> {
+        Ok(self.cook_stats(ctx).await?.and_then(|stats| stats.last_cooked_at))
+    }
+
+    /// how often the current user cooked the recipe
+    async fn times_cooked(&self, ctx: &Context<'_>) -> Result<i64> {
+        Ok(self.cook_stats(ctx).await?.map_or(0, |stats| stats.times_cooked))
+    }
+
+    /// whether the current user bookmarked the recipe
+    async fn is_favorite(&self, ctx: &Context<'_>) -> Result<bool> {
+        let Some(user) = ctx.data_opt::<users::Model>() else {
+            return Ok(false);
+        };
+
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let favorite = loader.load_one(FavoriteId(self.id, user.id)).await?;
+
+        Ok(favorite.is_some())
+    }
+}
+
+impl Model {
+    async fn cook_stats(&self, ctx: &Context<'_>) -> Result<Option<CookStats>> {
+        let Some(user) = ctx.data_opt::<users::Model>() else {
+            return Ok(None);
+        };
+
+        let loader = ctx.data_unchecked::<DataLoader<RecipesLoader>>();
+        let stats = loader.load_one(CookStatsId(self.id, user.id)).await?;
+
+        Ok(stats)
+    }
 }
 
 pub struct RecipesLoader {
@@ -231,6 +425,104 @@ impl Loader<StepId> for RecipesLoader {
     }
 }
 
+impl Loader<ForkedFromId> for RecipesLoader {
+    type Value = Model;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[ForkedFromId]) -> Result<HashMap<ForkedFromId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let recipes = Entity::find().filter(Column::Id.is_in(ids)).all(&self.conn).await?;
+
+        let map = recipes
+            .into_iter()
+            .map(|recipe| (ForkedFromId(recipe.id), recipe))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+impl Loader<VariantsId> for RecipesLoader {
+    type Value = Vec<Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[VariantsId]) -> Result<HashMap<VariantsId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let variants = Entity::find()
+            .filter(Column::ForkedFromId.is_in(ids))
+            .order_by_asc(Column::ForkedFromId)
+            .order_by_asc(Column::Name)
+            .all(&self.conn)
+            .await?;
+
+        let map = variants
+            .into_iter()
+            .chunk_by(|recipe| recipe.forked_from_id)
+            .into_iter()
+            .filter_map(|(key, group)| Some((VariantsId(key?), group.collect())))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+impl Loader<RevisionsId> for RecipesLoader {
+    type Value = Vec<recipe_revisions::Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[RevisionsId]) -> Result<HashMap<RevisionsId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let revisions = recipe_revisions::Entity::find()
+            .filter(recipe_revisions::Column::RecipeId.is_in(ids))
+            .order_by_asc(recipe_revisions::Column::RecipeId)
+            .order_by_desc(recipe_revisions::Column::Id)
+            .all(&self.conn)
+            .await?;
+
+        let map = revisions
+            .into_iter()
+            .chunk_by(|revision| revision.recipe_id)
+            .into_iter()
+            .map(|(key, group)| (RevisionsId(key), group.collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+#[derive(FromQueryResult)]
+struct RecipeIdAndCount {
+    recipe_id: i64,
+    count: i64,
+}
+
+impl Loader<CommentCountId> for RecipesLoader {
+    type Value = i64;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[CommentCountId]) -> Result<HashMap<CommentCountId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let counts = recipe_comments::Entity::find()
+            .select_only()
+            .column(recipe_comments::Column::RecipeId)
+            .column_as(recipe_comments::Column::Id.count(), "count")
+            .filter(recipe_comments::Column::RecipeId.is_in(ids))
+            .group_by(recipe_comments::Column::RecipeId)
+            .into_model::<RecipeIdAndCount>()
+            .all(&self.conn)
+            .await?;
+
+        Ok(counts
+            .into_iter()
+            .map(|count| (CommentCountId(count.recipe_id), count.count))
+            .collect())
+    }
+}
+
 #[derive(FromQueryResult, Debug)]
 struct RecipeIdAndRecipe {
     pub recipe_id: i64,
@@ -242,6 +534,8 @@ struct RecipeIdAndRecipe {
     pub inserted_at: DateTime,
     pub updated_at: DateTime,
     pub image: Option<String>,
+    pub visibility: Visibility,
+    pub forked_from_id: Option<i64>,
 }
 
 impl Loader<FittingRecipesId> for RecipesLoader {
@@ -282,6 +576,8 @@ impl Loader<FittingRecipesId> for RecipesLoader {
                         inserted_at: recipe.inserted_at,
                         updated_at: recipe.updated_at,
                         image: recipe.image,
+                        visibility: recipe.visibility,
+                        forked_from_id: recipe.forked_from_id,
                     })
                     .collect();
 
@@ -300,64 +596,234 @@ struct RecipeIdAndCalories {
     fat: f64,
     proteins: f64,
     alc: f64,
+    fibre: f64,
+    sugars: f64,
+    saturated_fat: f64,
+    salt: f64,
     base_value: Option<f64>,
     amount: Option<f64>,
 }
 
+/// Sums up the nutrition values of all ingredients of the given recipes, keyed by recipe id. Recipes without any
+/// ingredient amounts are missing in the result.
+pub async fn load_calories(ids: Vec<i64>, conn: &DatabaseConnection) -> Result<HashMap<i64, CaloriesResult>, DbErr> {
+    let calories = steps_ingredients::Entity::find()
+        .join(JoinType::InnerJoin, steps_ingredients::Relation::Steps.def())
+        .join(JoinType::InnerJoin, steps_ingredients::Relation::Ingredients.def())
+        .join(JoinType::LeftJoin, steps_ingredients::Relation::IngredientUnits.def())
+        .select_only()
+        .column_as(steps::Column::RecipeId, "recipe_id")
+        .column_as(ingredients::Column::Carbs, "carbs")
+        .column_as(ingredients::Column::Fat, "fat")
+        .column_as(ingredients::Column::Proteins, "proteins")
+        .column_as(ingredients::Column::Alc, "alc")
+        .column_as(ingredients::Column::Fibre, "fibre")
+        .column_as(ingredients::Column::Sugars, "sugars")
+        .column_as(ingredients::Column::SaturatedFat, "saturated_fat")
+        .column_as(ingredients::Column::Salt, "salt")
+        .column_as(ingredient_units::Column::BaseValue, "base_value")
+        .column_as(steps_ingredients::Column::Amount, "amount")
+        .filter(steps::Column::RecipeId.is_in(ids))
+        .filter(steps_ingredients::Column::Amount.is_not_null())
+        .order_by_asc(steps::Column::RecipeId)
+        .into_model::<RecipeIdAndCalories>()
+        .all(conn)
+        .await?;
+
+    let map = calories
+        .into_iter()
+        .chunk_by(|step| step.recipe_id)
+        .into_iter()
+        .map(|(key, group)| {
+            let calories = group.into_iter().fold(CaloriesResult::default(), |mut acc, row| {
+                let amount = row.amount.unwrap();
+                let grams = unit_conversion::to_reference(amount, row.base_value) / 100.0;
+
+                acc.carbs += row.carbs * grams;
+                acc.fats += row.fat * grams;
+                acc.proteins += row.proteins * grams;
+                acc.alcohol += row.alc * grams;
+                acc.fibre += row.fibre * grams;
+                acc.sugars += row.sugars * grams;
+                acc.saturated_fat += row.saturated_fat * grams;
+                acc.salt += row.salt * grams;
+                acc.calories = ingredients::energy(acc.carbs, acc.fats, acc.proteins, acc.alcohol, acc.fibre);
+
+                acc
+            });
+
+            (key, calories)
+        })
+        .collect();
+
+    Ok(map)
+}
+
 impl Loader<CaloriesId> for RecipesLoader {
     type Value = CaloriesResult;
     type Error = Arc<sea_orm::error::DbErr>;
 
     async fn load(&self, keys: &[CaloriesId]) -> Result<HashMap<CaloriesId, Self::Value>, Self::Error> {
         let ids = keys.iter().map(|k| k.0).collect_vec();
+        let calories = load_calories(ids, &self.conn).await?;
 
-        let calories = steps_ingredients::Entity::find()
+        Ok(calories
+            .into_iter()
+            .map(|(id, calories)| (CaloriesId(id), calories))
+            .collect())
+    }
+}
+
+#[derive(FromQueryResult, Clone, Debug)]
+struct CookStats {
+    recipe_id: i64,
+    user_id: i64,
+    average_rating: Option<f64>,
+    last_cooked_at: Option<Date>,
+    times_cooked: i64,
+}
+
+impl Loader<CookStatsId> for RecipesLoader {
+    type Value = CookStats;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[CookStatsId]) -> Result<HashMap<CookStatsId, Self::Value>, Self::Error> {
+        let recipe_ids = keys.iter().map(|k| k.0).unique().collect_vec();
+        let user_ids = keys.iter().map(|k| k.1).unique().collect_vec();
+
+        let stats = cook_log::Entity::find()
+            .select_only()
+            .column(cook_log::Column::RecipeId)
+            .column(cook_log::Column::UserId)
+            .column_as(Expr::cust("AVG(cook_log.rating)::double precision"), "average_rating")
+            .column_as(cook_log::Column::Date.max(), "last_cooked_at")
+            .column_as(cook_log::Column::Id.count(), "times_cooked")
+            .filter(cook_log::Column::RecipeId.is_in(recipe_ids))
+            .filter(cook_log::Column::UserId.is_in(user_ids))
+            .group_by(cook_log::Column::RecipeId)
+            .group_by(cook_log::Column::UserId)
+            .into_model::<CookStats>()
+            .all(&self.conn)
+            .await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|stats| (CookStatsId(stats.recipe_id, stats.user_id), stats))
+            .collect())
+    }
+}
+
+impl Loader<FavoriteId> for RecipesLoader {
+    type Value = favorites::Model;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[FavoriteId]) -> Result<HashMap<FavoriteId, Self::Value>, Self::Error> {
+        let recipe_ids = keys.iter().map(|k| k.0).unique().collect_vec();
+        let user_ids = keys.iter().map(|k| k.1).unique().collect_vec();
+
+        let favorites = favorites::Entity::find()
+            .filter(favorites::Column::RecipeId.is_in(recipe_ids))
+            .filter(favorites::Column::UserId.is_in(user_ids))
+            .all(&self.conn)
+            .await?;
+
+        Ok(favorites
+            .into_iter()
+            .map(|favorite| (FavoriteId(favorite.recipe_id, favorite.user_id), favorite))
+            .collect())
+    }
+}
+
+#[derive(FromQueryResult)]
+struct RecipeIdAndAllergen {
+    recipe_id: i64,
+    allergen: Allergen,
+}
+
+impl Loader<AllergensId> for RecipesLoader {
+    type Value = Vec<Allergen>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[AllergensId]) -> Result<HashMap<AllergensId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let allergens = steps_ingredients::Entity::find()
             .join(JoinType::InnerJoin, steps_ingredients::Relation::Steps.def())
-            .join(JoinType::InnerJoin, steps_ingredients::Relation::Ingredients.def())
-            .join(JoinType::LeftJoin, steps_ingredients::Relation::IngredientUnits.def())
+            .join(
+                JoinType::InnerJoin,
+                steps_ingredients::Entity::belongs_to(ingredient_allergens::Entity)
+                    .from(steps_ingredients::Column::IngredientId)
+                    .to(ingredient_allergens::Column::IngredientId)
+                    .into(),
+            )
             .select_only()
             .column_as(steps::Column::RecipeId, "recipe_id")
-            .column_as(ingredients::Column::Carbs, "carbs")
-            .column_as(ingredients::Column::Fat, "fat")
-            .column_as(ingredients::Column::Proteins, "proteins")
-            .column_as(ingredients::Column::Alc, "alc")
-            .column_as(ingredient_units::Column::BaseValue, "base_value")
-            .column_as(steps_ingredients::Column::Amount, "amount")
+            .column_as(ingredient_allergens::Column::Allergen, "allergen")
+            .distinct()
             .filter(steps::Column::RecipeId.is_in(ids))
-            .filter(steps_ingredients::Column::Amount.is_not_null())
             .order_by_asc(steps::Column::RecipeId)
-            .into_model::<RecipeIdAndCalories>()
+            .order_by_asc(ingredient_allergens::Column::Allergen)
+            .into_model::<RecipeIdAndAllergen>()
             .all(&self.conn)
             .await?;
 
-        let map: HashMap<CaloriesId, CaloriesResult> = calories
+        let map = allergens
             .into_iter()
-            .chunk_by(|step| step.recipe_id)
+            .chunk_by(|row| row.recipe_id)
             .into_iter()
-            .map(|(key, group)| {
-                let calories = group.into_iter().fold(
-                    CaloriesResult {
-                        carbs: 0.0,
-                        fats: 0.0,
-                        proteins: 0.0,
-                        alcohol: 0.0,
-                        calories: 0.0,
-                    },
-                    |mut acc, row| {
-                        let amount = row.amount.unwrap();
-                        let grams = row.base_value.map(|bv| bv * amount).unwrap_or(amount) / 100.0;
-
-                        acc.carbs += row.carbs * grams;
-                        acc.fats += row.fat * grams;
-                        acc.proteins += row.proteins * grams;
-                        acc.alcohol += row.alc * grams;
-                        acc.calories = acc.carbs * 4.1 + acc.fats * 9.3 + acc.proteins * 4.1 + acc.alcohol * 7.1;
-
-                        acc
-                    },
-                );
-
-                (CaloriesId(key), calories)
+            .map(|(key, group)| (AllergensId(key), group.map(|row| row.allergen).collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+#[derive(FromQueryResult)]
+struct RecipeIdAndDiets {
+    recipe_id: i64,
+    vegan: bool,
+    vegetarian: bool,
+    gluten_free: bool,
+    lactose_free: bool,
+}
+
+impl Loader<DietsId> for RecipesLoader {
+    type Value = Vec<Diet>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    /// Recipes without ingredients are missing in the result, they are suitable for every diet
+    async fn load(&self, keys: &[DietsId]) -> Result<HashMap<DietsId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let diets = steps_ingredients::Entity::find()
+            .join(JoinType::InnerJoin, steps_ingredients::Relation::Steps.def())
+            .join(JoinType::InnerJoin, steps_ingredients::Relation::Ingredients.def())
+            .select_only()
+            .column_as(steps::Column::RecipeId, "recipe_id")
+            .column_as(Expr::cust("BOOL_AND(ingredients.vegan)"), "vegan")
+            .column_as(Expr::cust("BOOL_AND(ingredients.vegetarian)"), "vegetarian")
+            .column_as(Expr::cust("BOOL_AND(ingredients.gluten_free)"), "gluten_free")
+            .column_as(Expr::cust("BOOL_AND(ingredients.lactose_free)"), "lactose_free")
+            .filter(steps::Column::RecipeId.is_in(ids))
+            .group_by(steps::Column::RecipeId)
+            .into_model::<RecipeIdAndDiets>()
+            .all(&self.conn)
+            .await?;
+
+        let map = diets
+            .into_iter()
+            .map(|row| {
+                let diets = [
+                    (Diet::Vegan, row.vegan),
+                    (Diet::Vegetarian, row.vegetarian),
+                    (Diet::GlutenFree, row.gluten_free),
+                    (Diet::LactoseFree, row.lactose_free),
+                ]
+                .into_iter()
+                .filter_map(|(diet, suitable)| suitable.then_some(diet))
+                .collect();
+
+                (DietsId(row.recipe_id), diets)
             })
             .collect();
 
@@ -0,0 +1,88 @@
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::shopping_lists::{IngredientId, ShoppingListLoader, UnitId};
+use crate::{ingredient_units, ingredients};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "shopping_list_items")]
+#[graphql(complex, name = "ShoppingListItem")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub shopping_list_id: i64,
+    pub ingredient_id: Option<i64>,
+    pub unit_id: Option<i64>,
+    pub name: String,
+    pub amount: Option<f64>,
+    pub note: Option<String>,
+    pub checked: bool,
+    /// items added or edited by the user; these are left alone when the list is regenerated
+    pub manual: bool,
+    /// generated items the user deleted; they are kept so regenerating the list doesn't bring them back
+    #[graphql(skip)]
+    pub removed: bool,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::shopping_lists::Entity",
+        from = "Column::ShoppingListId",
+        to = "super::shopping_lists::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    ShoppingLists,
+    #[sea_orm(
+        belongs_to = "super::ingredients::Entity",
+        from = "Column::IngredientId",
+        to = "super::ingredients::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Ingredients,
+    #[sea_orm(
+        belongs_to = "super::ingredient_units::Entity",
+        from = "Column::UnitId",
+        to = "super::ingredient_units::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    IngredientUnits,
+}
+
+impl Related<super::shopping_lists::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ShoppingLists.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    async fn ingredient(&self, ctx: &Context<'_>) -> Result<Option<ingredients::Model>> {
+        if let Some(ingredient_id) = self.ingredient_id {
+            let loader = ctx.data_unchecked::<DataLoader<ShoppingListLoader>>();
+            let ingredient = loader.load_one(IngredientId(ingredient_id)).await?;
+            Ok(ingredient)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn unit(&self, ctx: &Context<'_>) -> Result<Option<ingredient_units::Model>> {
+        if let Some(unit_id) = self.unit_id {
+            let loader = ctx.data_unchecked::<DataLoader<ShoppingListLoader>>();
+            let unit = loader.load_one(UnitId(unit_id)).await?;
+            Ok(unit)
+        } else {
+            Ok(None)
+        }
+    }
+}
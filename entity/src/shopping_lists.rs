@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use itertools::Itertools;
+use sea_orm::QueryOrder;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ingredient_units, ingredients, shopping_list_items};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "shopping_lists")]
+#[graphql(complex, name = "ShoppingList")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// the user who created the list
+    pub user_id: i64,
+    pub household_id: i64,
+    pub week: Date,
+    pub name: String,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::households::Entity",
+        from = "Column::HouseholdId",
+        to = "super::households::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Household,
+    #[sea_orm(has_many = "super::shopping_list_items::Entity")]
+    ShoppingListItems,
+}
+
+impl Related<super::shopping_list_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ShoppingListItems.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    async fn items(&self, ctx: &Context<'_>) -> Result<Vec<shopping_list_items::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<ShoppingListLoader>>();
+        let items: Option<Vec<shopping_list_items::Model>> = loader.load_one(ItemsId(self.id)).await?;
+        Ok(items.unwrap_or_default())
+    }
+}
+
+pub struct ShoppingListLoader {
+    pub conn: DatabaseConnection,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct ItemsId(i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub(crate) struct IngredientId(pub i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub(crate) struct UnitId(pub i64);
+
+impl Loader<ItemsId> for ShoppingListLoader {
+    type Value = Vec<shopping_list_items::Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[ItemsId]) -> Result<HashMap<ItemsId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let items = shopping_list_items::Entity::find()
+            .filter(shopping_list_items::Column::ShoppingListId.is_in(ids))
+            .filter(shopping_list_items::Column::Removed.eq(false))
+            .order_by_asc(shopping_list_items::Column::ShoppingListId)
+            .order_by_asc(shopping_list_items::Column::Checked)
+            .order_by_asc(shopping_list_items::Column::Name)
+            .order_by_asc(shopping_list_items::Column::Id)
+            .all(&self.conn)
+            .await?;
+
+        let map = items
+            .into_iter()
+            .chunk_by(|item| item.shopping_list_id)
+            .into_iter()
+            .map(|(key, group)| (ItemsId(key), group.collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+impl Loader<IngredientId> for ShoppingListLoader {
+    type Value = ingredients::Model;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[IngredientId]) -> Result<HashMap<IngredientId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|id| id.0).collect_vec();
+
+        let ingredients = ingredients::Entity::find()
+            .filter(ingredients::Column::Id.is_in(ids))
+            .all(&self.conn)
+            .await?;
+
+        let map = ingredients
+            .into_iter()
+            .map(|ingredient| (IngredientId(ingredient.id), ingredient))
+            .collect();
+
+        Ok(map)
+    }
+}
+
+impl Loader<UnitId> for ShoppingListLoader {
+    type Value = ingredient_units::Model;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[UnitId]) -> Result<HashMap<UnitId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|id| id.0).collect_vec();
+
+        let units = ingredient_units::Entity::find()
+            .filter(ingredient_units::Column::Id.is_in(ids))
+            .all(&self.conn)
+            .await?;
+
+        let map = units.into_iter().map(|unit| (UnitId(unit.id), unit)).collect();
+
+        Ok(map)
+    }
+}
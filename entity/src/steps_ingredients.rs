@@ -5,9 +5,11 @@ use std::sync::Arc;
 use async_graphql::dataloader::*;
 use async_graphql::*;
 use itertools::Itertools;
+use sea_orm::QueryOrder;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::unit_conversion::{self, ConvertedAmount};
 use crate::{ingredient_units, ingredients};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, SimpleObject)]
@@ -81,6 +83,8 @@ pub struct StepIngredientLoader {
 struct UnitId(pub i64);
 #[derive(Clone, Eq, PartialEq, Hash)]
 struct IngredientId(pub i64);
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct IngredientUnitsId(pub i64);
 
 #[ComplexObject]
 impl Model {
@@ -99,6 +103,39 @@ impl Model {
         let ingredient = loader.load_one(IngredientId(self.ingredient_id)).await?;
         ingredient.ok_or_else(|| "Not found".into())
     }
+
+    /// the amount for `servings` servings, rounded sensibly for the unit; stored amounts are per serving
+    async fn scaled_amount(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(minimum = 1))] servings: i32,
+    ) -> Result<Option<f64>> {
+        let Some(amount) = self.amount else {
+            return Ok(None);
+        };
+
+        let unit = self.unit(ctx).await?;
+
+        let amount = unit_conversion::scale(amount, servings);
+        Ok(Some(unit_conversion::round_amount(amount, unit.map(|u| u.identifier))))
+    }
+
+    /// the amount converted to every compatible unit of the ingredient
+    async fn converted_amounts(&self, ctx: &Context<'_>) -> Result<Vec<ConvertedAmount>> {
+        let Some(amount) = self.amount else {
+            return Ok(vec![]);
+        };
+
+        let loader = ctx.data_unchecked::<DataLoader<StepIngredientLoader>>();
+        let ingredient = self.ingredient(ctx).await?;
+        let unit = self.unit(ctx).await?;
+        let units = loader
+            .load_one(IngredientUnitsId(self.ingredient_id))
+            .await?
+            .unwrap_or_default();
+
+        Ok(unit_conversion::compatible_amounts(amount, unit.as_ref(), &ingredient, &units))
+    }
 }
 
 impl Loader<UnitId> for StepIngredientLoader {
@@ -141,3 +178,28 @@ impl Loader<IngredientId> for StepIngredientLoader {
         Ok(map)
     }
 }
+
+impl Loader<IngredientUnitsId> for StepIngredientLoader {
+    type Value = Vec<ingredient_units::Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[IngredientUnitsId]) -> Result<HashMap<IngredientUnitsId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|id| id.0).collect_vec();
+
+        let units = ingredient_units::Entity::find()
+            .filter(ingredient_units::Column::IngredientId.is_in(ids))
+            .order_by_asc(ingredient_units::Column::IngredientId)
+            .order_by_asc(ingredient_units::Column::Id)
+            .all(&self.conn)
+            .await?;
+
+        let map = units
+            .into_iter()
+            .chunk_by(|unit| unit.ingredient_id)
+            .into_iter()
+            .map(|(key, group)| (IngredientUnitsId(key), group.collect()))
+            .collect();
+
+        Ok(map)
+    }
+}
@@ -0,0 +1,140 @@
+//! Conversion between the units an ingredient amount can be given in.
+//!
+//! Every ingredient has a reference (g or ml) its nutrition values relate to. Amounts without a unit are given in
+//! that reference, amounts with an ingredient unit are multiples of the unit's `base_value` (which is in the
+//! reference as well). Converting between g and ml needs the ingredient's density.
+
+use async_graphql::*;
+use serde::Serialize;
+
+use crate::ingredient_units::{self, Units};
+use crate::ingredients::{self, Reference};
+
+/// Something an amount can be converted to
+#[derive(Clone, Copy, Debug)]
+pub enum Target<'a> {
+    Reference(Reference),
+    Unit(&'a ingredient_units::Model),
+}
+
+#[derive(Clone, Debug, Serialize, SimpleObject)]
+pub struct ConvertedAmount {
+    /// set when the amount is given in g or ml
+    pub reference: Option<Reference>,
+    /// set when the amount is given in one of the ingredient's units
+    pub unit: Option<ingredient_units::Model>,
+    pub amount: f64,
+}
+
+/// Converts an amount given in a unit with the given base value to the ingredient's reference. Amounts without a
+/// unit already are in the reference.
+pub fn to_reference(amount: f64, base_value: Option<f64>) -> f64 {
+    base_value.map_or(amount, |base_value| amount * base_value)
+}
+
+/// Converts an amount in the ingredient's reference to `target`
+pub fn convert_reference(amount: f64, ingredient: &ingredients::Model, target: Reference) -> Option<f64> {
+    convert_between(amount, ingredient.reference, target, ingredient.density)
+}
+
+fn convert_between(amount: f64, from: Reference, to: Reference, density: Option<f64>) -> Option<f64> {
+    match (from, to) {
+        (Reference::G, Reference::G) | (Reference::ML, Reference::ML) => Some(amount),
+        (Reference::ML, Reference::G) => density.map(|density| amount * density),
+        (Reference::G, Reference::ML) => density.filter(|density| *density > 0.0).map(|density| amount / density),
+    }
+}
+
+/// The base value a unit has by default for an ingredient with the given reference and density, derived from the
+/// unit's standard size. Returns `None` for units without a standard size or if a density would be needed but is
+/// unknown.
+pub fn default_base_value(identifier: Units, reference: Reference, density: Option<f64>) -> Option<f64> {
+    let (unit_reference, amount) = identifier.standard_measure()?;
+    convert_between(amount, unit_reference, reference, density)
+}
+
+/// Converts an amount given in `from` (or in the reference when `None`) to `target`. Returns `None` if the two are
+/// not compatible, e.g. when converting between g and ml for an ingredient without a density.
+pub fn convert(
+    amount: f64,
+    from: Option<&ingredient_units::Model>,
+    target: Target<'_>,
+    ingredient: &ingredients::Model,
+) -> Option<f64> {
+    let reference_amount = to_reference(amount, from.map(|unit| unit.base_value));
+
+    match target {
+        Target::Reference(reference) => convert_reference(reference_amount, ingredient, reference),
+        Target::Unit(unit) if unit.ingredient_id == ingredient.id && unit.base_value > 0.0 => {
+            Some(reference_amount / unit.base_value)
+        }
+        Target::Unit(_) => None,
+    }
+}
+
+/// Lists the amount in every unit it can be converted to: g, ml and all of the ingredient's units
+pub fn compatible_amounts(
+    amount: f64,
+    from: Option<&ingredient_units::Model>,
+    ingredient: &ingredients::Model,
+    units: &[ingredient_units::Model],
+) -> Vec<ConvertedAmount> {
+    let references = [Reference::G, Reference::ML].into_iter().filter_map(|reference| {
+        convert(amount, from, Target::Reference(reference), ingredient).map(|amount| ConvertedAmount {
+            reference: Some(reference),
+            unit: None,
+            amount,
+        })
+    });
+
+    let units = units.iter().filter_map(|unit| {
+        convert(amount, from, Target::Unit(unit), ingredient).map(|amount| ConvertedAmount {
+            reference: None,
+            unit: Some(unit.clone()),
+            amount,
+        })
+    });
+
+    references.chain(units).collect()
+}
+
+/// Scales an amount of one serving to `servings`. Step ingredient amounts are stored per serving, like the Bring
+/// export and the weekplan's portions have always treated them.
+pub fn scale(amount: f64, servings: i32) -> f64 {
+    amount * servings as f64
+}
+
+/// Rounds a (scaled) amount to something that makes sense in the kitchen: whole pieces, half spoons and a precision
+/// for g/ml that depends on the magnitude. Countable amounts never drop to zero.
+pub fn round_amount(amount: f64, unit: Option<Units>) -> f64 {
+    let round_to = |step: f64| (amount / step).round() * step;
+
+    match unit {
+        Some(
+            Units::PCS
+            | Units::CAN
+            | Units::PACKET
+            | Units::BUNCH
+            | Units::CLOVE
+            | Units::SLICE
+            | Units::SPRIG
+            | Units::HANDFUL
+            | Units::SKOSH
+            | Units::PINCH,
+        ) => round_to(1.0).max(1.0),
+        Some(Units::TBSP | Units::TSP | Units::CL | Units::DL) => round_to(0.5).max(0.5),
+        Some(Units::CUP) => round_to(0.25).max(0.25),
+        Some(Units::KG | Units::L) => round_to(0.05).max(0.05),
+        Some(Units::G | Units::ML) | None => {
+            if amount < 10.0 {
+                round_to(0.5)
+            } else if amount < 100.0 {
+                round_to(1.0)
+            } else if amount < 1000.0 {
+                round_to(5.0)
+            } else {
+                round_to(10.0)
+            }
+        }
+    }
+}
@@ -28,6 +28,10 @@ pub struct Model {
     pub avatar: Option<String>,
     pub name: Option<String>,
     pub role: Role,
+    pub household_id: i64,
+    /// secret token granting read access to the household's weekplan as an iCalendar feed
+    #[graphql(skip)]
+    pub calendar_token: Option<String>,
     pub inserted_at: DateTime,
     pub updated_at: DateTime,
 }
@@ -36,6 +40,14 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::recipes::Entity")]
     Recipes,
+    #[sea_orm(
+        belongs_to = "super::households::Entity",
+        from = "Column::HouseholdId",
+        to = "super::households::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Restrict"
+    )]
+    Household,
 }
 
 impl Related<super::recipes::Entity> for Entity {
@@ -44,6 +56,12 @@ impl Related<super::recipes::Entity> for Entity {
     }
 }
 
+impl Related<super::households::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Household.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 #[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
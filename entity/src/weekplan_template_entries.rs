@@ -0,0 +1,58 @@
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::weekplans::{MealSlot, WeekplanLoader};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "weekplan_template_entries")]
+#[graphql(complex, name = "WeekplanTemplateEntry")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub template_id: i64,
+    /// day of the week, 0 is Monday
+    pub weekday: i32,
+    pub meal_slot: MealSlot,
+    pub recipe_id: i64,
+    pub portions: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::weekplan_templates::Entity",
+        from = "Column::TemplateId",
+        to = "super::weekplan_templates::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Template,
+    #[sea_orm(
+        belongs_to = "super::recipes::Entity",
+        from = "Column::RecipeId",
+        to = "super::recipes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Recipe,
+}
+
+impl Related<super::weekplan_templates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Template.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    async fn recipe(&self, ctx: &Context<'_>) -> Result<Option<super::recipes::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<WeekplanLoader>>();
+        let recipe = loader.load_one(self.recipe_id).await?;
+
+        Ok(recipe)
+    }
+}
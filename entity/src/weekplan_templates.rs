@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use itertools::Itertools;
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use crate::weekplan_template_entries;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
+#[sea_orm(table_name = "weekplan_templates")]
+#[graphql(complex, name = "WeekplanTemplate")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub inserted_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(has_many = "super::weekplan_template_entries::Entity")]
+    Entries,
+}
+
+impl Related<super::weekplan_template_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Entries.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[ComplexObject]
+impl Model {
+    /// the template's entries ordered by weekday and meal slot
+    async fn entries(&self, ctx: &Context<'_>) -> Result<Vec<weekplan_template_entries::Model>> {
+        let loader = ctx.data_unchecked::<DataLoader<WeekplanTemplatesLoader>>();
+        let entries = loader.load_one(EntriesId(self.id)).await?;
+        Ok(entries.unwrap_or_default())
+    }
+}
+
+pub struct WeekplanTemplatesLoader {
+    pub conn: DatabaseConnection,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct EntriesId(i64);
+
+impl Loader<EntriesId> for WeekplanTemplatesLoader {
+    type Value = Vec<weekplan_template_entries::Model>;
+    type Error = Arc<sea_orm::error::DbErr>;
+
+    async fn load(&self, keys: &[EntriesId]) -> Result<HashMap<EntriesId, Self::Value>, Self::Error> {
+        let ids = keys.iter().map(|k| k.0).collect_vec();
+
+        let entries = weekplan_template_entries::Entity::find()
+            .filter(weekplan_template_entries::Column::TemplateId.is_in(ids))
+            .order_by_asc(weekplan_template_entries::Column::TemplateId)
+            .order_by_asc(weekplan_template_entries::Column::Weekday)
+            .order_by_asc(weekplan_template_entries::Column::Id)
+            .all(&self.conn)
+            .await?;
+
+        let map = entries
+            .into_iter()
+            .chunk_by(|entry| entry.template_id)
+            .into_iter()
+            .map(|(key, group)| {
+                let entries = group
+                    .sorted_by_key(|entry| (entry.weekday, entry.meal_slot as u8))
+                    .collect();
+                (EntriesId(key), entries)
+            })
+            .collect();
+
+        Ok(map)
+    }
+}
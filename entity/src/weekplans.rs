@@ -6,16 +6,51 @@ use async_graphql::*;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// The meals of a day, the variants are ordered by the time of day
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Enum,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum MealSlot {
+    #[sea_orm(string_value = "breakfast")]
+    Breakfast,
+    #[sea_orm(string_value = "lunch")]
+    Lunch,
+    #[default]
+    #[sea_orm(string_value = "dinner")]
+    Dinner,
+    #[sea_orm(string_value = "snack")]
+    Snack,
+}
+
+impl MealSlot {
+    /// The tags recipes for the slot are picked by unless others are given; lunch and dinner use the tags the week is
+    /// planned with
+    pub fn default_tags(&self) -> Option<Vec<String>> {
+        match self {
+            MealSlot::Breakfast => Some(vec!["frühstück".to_owned()]),
+            MealSlot::Snack => Some(vec!["snack".to_owned()]),
+            MealSlot::Lunch | MealSlot::Dinner => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, SimpleObject)]
 #[sea_orm(table_name = "weekplans")]
 #[graphql(complex, name = "Weekplan")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
+    /// the user who planned the entry
     pub user_id: i64,
+    pub household_id: i64,
     pub date: Date,
+    pub meal_slot: MealSlot,
     pub recipe_id: i64,
     pub portions: i32,
+    /// set when the meal is the leftovers of another entry; its ingredients are part of that entry's portions
+    pub leftovers_of_id: Option<i64>,
     pub inserted_at: DateTime,
     pub updated_at: DateTime,
 }
@@ -34,6 +69,22 @@ pub enum Relation {
         to = "super::users::Column::Id"
     )]
     User,
+    #[sea_orm(
+        belongs_to = "super::households::Entity",
+        from = "Column::HouseholdId",
+        to = "super::households::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Household,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::LeftoversOfId",
+        to = "Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    LeftoversOf,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -10,6 +10,26 @@ mod m20221221_135800_add_name_to_steps;
 mod m20221221_145615_add_default_quantity_to_recipes;
 mod m20221228_085431_create_week_plan;
 mod m20230101_124914_make_tag_name_not_null;
+mod m20261018_090000_create_shopping_lists;
+mod m20261018_100000_add_recipes_search_vector;
+mod m20261018_110000_add_density_to_ingredients;
+mod m20261018_130000_create_cook_log;
+mod m20261018_140000_create_nutrition_targets;
+mod m20261018_150000_add_extended_nutrients_to_ingredients;
+mod m20261018_160000_add_allergens_and_diets_to_ingredients;
+mod m20261018_170000_create_pantry_items;
+mod m20261018_180000_create_collections;
+mod m20261018_190000_add_recipe_visibility;
+mod m20261018_200000_create_recipe_revisions;
+mod m20261018_210000_add_forked_from_to_recipes;
+mod m20261018_220000_create_recipe_comments;
+mod m20261018_230000_create_favorites;
+mod m20261018_240000_create_households;
+mod m20261018_250000_add_meal_slot_to_weekplans;
+mod m20261018_260000_create_weekplan_templates;
+mod m20261018_270000_add_calendar_token_to_users;
+mod m20261018_280000_add_leftovers_of_to_weekplans;
+mod m20261018_290000_add_removed_to_shopping_list_items;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
@@ -23,6 +43,26 @@ impl MigratorTrait for Migrator {
             Box::new(m20221221_145615_add_default_quantity_to_recipes::Migration),
             Box::new(m20221228_085431_create_week_plan::Migration),
             Box::new(m20230101_124914_make_tag_name_not_null::Migration),
+            Box::new(m20261018_090000_create_shopping_lists::Migration),
+            Box::new(m20261018_100000_add_recipes_search_vector::Migration),
+            Box::new(m20261018_110000_add_density_to_ingredients::Migration),
+            Box::new(m20261018_130000_create_cook_log::Migration),
+            Box::new(m20261018_140000_create_nutrition_targets::Migration),
+            Box::new(m20261018_150000_add_extended_nutrients_to_ingredients::Migration),
+            Box::new(m20261018_160000_add_allergens_and_diets_to_ingredients::Migration),
+            Box::new(m20261018_170000_create_pantry_items::Migration),
+            Box::new(m20261018_180000_create_collections::Migration),
+            Box::new(m20261018_190000_add_recipe_visibility::Migration),
+            Box::new(m20261018_200000_create_recipe_revisions::Migration),
+            Box::new(m20261018_210000_add_forked_from_to_recipes::Migration),
+            Box::new(m20261018_220000_create_recipe_comments::Migration),
+            Box::new(m20261018_230000_create_favorites::Migration),
+            Box::new(m20261018_240000_create_households::Migration),
+            Box::new(m20261018_250000_add_meal_slot_to_weekplans::Migration),
+            Box::new(m20261018_260000_create_weekplan_templates::Migration),
+            Box::new(m20261018_270000_add_calendar_token_to_users::Migration),
+            Box::new(m20261018_280000_add_leftovers_of_to_weekplans::Migration),
+            Box::new(m20261018_290000_add_removed_to_shopping_list_items::Migration),
         ]
     }
 }
@@ -45,6 +45,7 @@ impl MigrationTrait for Migration {
 
 /// Learn more at https://docs.rs/sea-query#iden
 #[derive(Iden)]
+#[allow(clippy::enum_variant_names)]
 enum Fitting {
     Table,
     RecipeId,
@@ -0,0 +1,166 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShoppingLists::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ShoppingLists::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ShoppingLists::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(ShoppingLists::Week).date().not_null())
+                    .col(ColumnDef::new(ShoppingLists::Name).string().not_null())
+                    .col(ColumnDef::new(ShoppingLists::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(ShoppingLists::UpdatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ShoppingLists::Table, ShoppingLists::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("shopping_lists_user_id_week_unique")
+                    .table(ShoppingLists::Table)
+                    .col(ShoppingLists::UserId)
+                    .col(ShoppingLists::Week)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShoppingListItems::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ShoppingListItems::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ShoppingListItems::ShoppingListId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ShoppingListItems::IngredientId).big_integer())
+                    .col(ColumnDef::new(ShoppingListItems::UnitId).big_integer())
+                    .col(ColumnDef::new(ShoppingListItems::Name).string().not_null())
+                    .col(ColumnDef::new(ShoppingListItems::Amount).double())
+                    .col(ColumnDef::new(ShoppingListItems::Note).string())
+                    .col(
+                        ColumnDef::new(ShoppingListItems::Checked)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ShoppingListItems::Manual)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(ShoppingListItems::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(ShoppingListItems::UpdatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ShoppingListItems::Table, ShoppingListItems::ShoppingListId)
+                            .to(ShoppingLists::Table, ShoppingLists::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ShoppingListItems::Table, ShoppingListItems::IngredientId)
+                            .to(Ingredients::Table, Ingredients::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ShoppingListItems::Table, ShoppingListItems::UnitId)
+                            .to(IngredientUnits::Table, IngredientUnits::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ShoppingListItems::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ShoppingLists::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum ShoppingLists {
+    Table,
+    Id,
+    UserId,
+    Week,
+    Name,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum ShoppingListItems {
+    Table,
+    Id,
+    ShoppingListId,
+    IngredientId,
+    UnitId,
+    Name,
+    Amount,
+    Note,
+    Checked,
+    Manual,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Ingredients {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum IngredientUnits {
+    Table,
+    Id,
+}
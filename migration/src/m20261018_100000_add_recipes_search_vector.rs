@@ -0,0 +1,123 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// A generated column may only reference its own row, but the search vector spans steps and ingredients as well. So
+// the column is kept up to date by triggers on every table contributing to it.
+const UP: &[&str] = &[
+    "ALTER TABLE recipes ADD COLUMN search_vector tsvector NOT NULL DEFAULT ''::tsvector",
+    r#"CREATE OR REPLACE FUNCTION recipes_search_vector(rid bigint) RETURNS tsvector AS $$
+  SELECT
+    setweight(to_tsvector('german', coalesce(r.name, '')), 'A') ||
+    setweight(to_tsvector('german', coalesce((
+      SELECT string_agg(i.name, ' ')
+      FROM steps s
+      INNER JOIN steps_ingridients si ON si.step_id = s.id
+      INNER JOIN ingredients i ON i.id = si.ingredient_id
+      WHERE s.recipe_id = r.id
+    ), '')), 'B') ||
+    setweight(to_tsvector('german', coalesce(r.description, '')), 'C') ||
+    setweight(to_tsvector('german', coalesce((
+      SELECT string_agg(coalesce(s.name, '') || ' ' || coalesce(s.description, ''), ' ')
+      FROM steps s
+      WHERE s.recipe_id = r.id
+    ), '')), 'D')
+  FROM recipes r
+  WHERE r.id = rid
+$$ LANGUAGE sql STABLE"#,
+    r#"CREATE OR REPLACE FUNCTION recipes_refresh_search_vector(rid bigint) RETURNS void AS $$
+  UPDATE recipes SET search_vector = coalesce(recipes_search_vector(rid), ''::tsvector) WHERE id = rid
+$$ LANGUAGE sql"#,
+    r#"CREATE OR REPLACE FUNCTION recipes_search_vector_trigger() RETURNS trigger AS $$
+BEGIN
+  PERFORM recipes_refresh_search_vector(NEW.id);
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql"#,
+    r#"CREATE OR REPLACE FUNCTION steps_search_vector_trigger() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP IN ('UPDATE', 'DELETE') THEN
+    PERFORM recipes_refresh_search_vector(OLD.recipe_id);
+  END IF;
+
+  IF TG_OP IN ('INSERT', 'UPDATE') THEN
+    PERFORM recipes_refresh_search_vector(NEW.recipe_id);
+  END IF;
+
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql"#,
+    r#"CREATE OR REPLACE FUNCTION steps_ingridients_search_vector_trigger() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP IN ('UPDATE', 'DELETE') THEN
+    PERFORM recipes_refresh_search_vector(recipe_id) FROM steps WHERE id = OLD.step_id;
+  END IF;
+
+  IF TG_OP IN ('INSERT', 'UPDATE') THEN
+    PERFORM recipes_refresh_search_vector(recipe_id) FROM steps WHERE id = NEW.step_id;
+  END IF;
+
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql"#,
+    r#"CREATE OR REPLACE FUNCTION ingredients_search_vector_trigger() RETURNS trigger AS $$
+BEGIN
+  PERFORM recipes_refresh_search_vector(s.recipe_id)
+  FROM steps s
+  INNER JOIN steps_ingridients si ON si.step_id = s.id
+  WHERE si.ingredient_id = NEW.id
+  GROUP BY s.recipe_id;
+
+  RETURN NULL;
+END
+$$ LANGUAGE plpgsql"#,
+    "CREATE TRIGGER recipes_search_vector_update AFTER INSERT OR UPDATE OF name, description ON recipes FOR EACH ROW \
+     EXECUTE FUNCTION recipes_search_vector_trigger()",
+    "CREATE TRIGGER steps_search_vector_update AFTER INSERT OR UPDATE OR DELETE ON steps FOR EACH ROW EXECUTE \
+     FUNCTION steps_search_vector_trigger()",
+    "CREATE TRIGGER steps_ingridients_search_vector_update AFTER INSERT OR UPDATE OR DELETE ON steps_ingridients FOR \
+     EACH ROW EXECUTE FUNCTION steps_ingridients_search_vector_trigger()",
+    "CREATE TRIGGER ingredients_search_vector_update AFTER UPDATE OF name ON ingredients FOR EACH ROW EXECUTE \
+     FUNCTION ingredients_search_vector_trigger()",
+    "UPDATE recipes SET search_vector = coalesce(recipes_search_vector(id), ''::tsvector)",
+    "CREATE INDEX recipes_search_vector_idx ON recipes USING GIN (search_vector)",
+];
+
+const DOWN: &[&str] = &[
+    "DROP TRIGGER IF EXISTS ingredients_search_vector_update ON ingredients",
+    "DROP TRIGGER IF EXISTS steps_ingridients_search_vector_update ON steps_ingridients",
+    "DROP TRIGGER IF EXISTS steps_search_vector_update ON steps",
+    "DROP TRIGGER IF EXISTS recipes_search_vector_update ON recipes",
+    "DROP FUNCTION IF EXISTS ingredients_search_vector_trigger()",
+    "DROP FUNCTION IF EXISTS steps_ingridients_search_vector_trigger()",
+    "DROP FUNCTION IF EXISTS steps_search_vector_trigger()",
+    "DROP FUNCTION IF EXISTS recipes_search_vector_trigger()",
+    "DROP FUNCTION IF EXISTS recipes_refresh_search_vector(bigint)",
+    "DROP FUNCTION IF EXISTS recipes_search_vector(bigint)",
+    "DROP INDEX IF EXISTS recipes_search_vector_idx",
+    "ALTER TABLE recipes DROP COLUMN IF EXISTS search_vector",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for sql in UP {
+            let stmt = Statement::from_string(manager.get_database_backend(), (*sql).to_owned());
+            manager.get_connection().execute(stmt).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for sql in DOWN {
+            let stmt = Statement::from_string(manager.get_database_backend(), (*sql).to_owned());
+            manager.get_connection().execute(stmt).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CookLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CookLog::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CookLog::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(CookLog::RecipeId).big_integer().not_null())
+                    .col(ColumnDef::new(CookLog::WeekplanId).big_integer())
+                    .col(ColumnDef::new(CookLog::Date).date().not_null())
+                    .col(ColumnDef::new(CookLog::Rating).integer())
+                    .col(ColumnDef::new(CookLog::Note).text())
+                    .col(ColumnDef::new(CookLog::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(CookLog::UpdatedAt).timestamp().not_null())
+                    .check(Expr::col(CookLog::Rating).between(1, 5))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CookLog::Table, CookLog::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CookLog::Table, CookLog::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CookLog::Table, CookLog::WeekplanId)
+                            .to(Weekplans::Table, Weekplans::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("cook_log_recipe_id_user_id_idx")
+                    .table(CookLog::Table)
+                    .col(CookLog::RecipeId)
+                    .col(CookLog::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("cook_log_user_id_date_idx")
+                    .table(CookLog::Table)
+                    .col(CookLog::UserId)
+                    .col(CookLog::Date)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(CookLog::Table).to_owned()).await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum CookLog {
+    Table,
+    Id,
+    UserId,
+    RecipeId,
+    WeekplanId,
+    Date,
+    Rating,
+    Note,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Recipes {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Weekplans {
+    Table,
+    Id,
+}
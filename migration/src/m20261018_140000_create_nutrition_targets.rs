@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NutritionTargets::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(NutritionTargets::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(NutritionTargets::UserId)
+                            .big_integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(NutritionTargets::Calories).double())
+                    .col(ColumnDef::new(NutritionTargets::Proteins).double())
+                    .col(ColumnDef::new(NutritionTargets::Carbs).double())
+                    .col(ColumnDef::new(NutritionTargets::Fats).double())
+                    .col(ColumnDef::new(NutritionTargets::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(NutritionTargets::UpdatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(NutritionTargets::Table, NutritionTargets::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NutritionTargets::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum NutritionTargets {
+    Table,
+    Id,
+    UserId,
+    Calories,
+    Proteins,
+    Carbs,
+    Fats,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .add_column(ColumnDef::new(Ingredients::Fibre).double().not_null().default(0.0))
+                    .add_column(ColumnDef::new(Ingredients::Sugars).double().not_null().default(0.0))
+                    .add_column(
+                        ColumnDef::new(Ingredients::SaturatedFat)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .add_column(ColumnDef::new(Ingredients::Salt).double().not_null().default(0.0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .drop_column(Ingredients::Fibre)
+                    .drop_column(Ingredients::Sugars)
+                    .drop_column(Ingredients::SaturatedFat)
+                    .drop_column(Ingredients::Salt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Ingredients {
+    Table,
+    Fibre,
+    Sugars,
+    SaturatedFat,
+    Salt,
+}
@@ -0,0 +1,109 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .add_column(ColumnDef::new(Ingredients::Vegan).boolean().not_null().default(false))
+                    .add_column(
+                        ColumnDef::new(Ingredients::Vegetarian)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(
+                        ColumnDef::new(Ingredients::GlutenFree)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(
+                        ColumnDef::new(Ingredients::LactoseFree)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(IngredientAllergens::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IngredientAllergens::IngredientId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(IngredientAllergens::Allergen).string_len(32).not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(IngredientAllergens::IngredientId)
+                            .col(IngredientAllergens::Allergen),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(IngredientAllergens::Table, IngredientAllergens::IngredientId)
+                            .to(Ingredients::Table, Ingredients::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ingredient_allergens_allergen_idx")
+                    .table(IngredientAllergens::Table)
+                    .col(IngredientAllergens::Allergen)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IngredientAllergens::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .drop_column(Ingredients::Vegan)
+                    .drop_column(Ingredients::Vegetarian)
+                    .drop_column(Ingredients::GlutenFree)
+                    .drop_column(Ingredients::LactoseFree)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Ingredients {
+    Table,
+    Id,
+    Vegan,
+    Vegetarian,
+    GlutenFree,
+    LactoseFree,
+}
+
+#[derive(Iden)]
+enum IngredientAllergens {
+    Table,
+    IngredientId,
+    Allergen,
+}
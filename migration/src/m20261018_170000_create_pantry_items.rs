@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PantryItems::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PantryItems::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PantryItems::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(PantryItems::IngredientId).big_integer().not_null())
+                    .col(ColumnDef::new(PantryItems::UnitId).big_integer())
+                    .col(ColumnDef::new(PantryItems::Amount).double().not_null())
+                    .col(ColumnDef::new(PantryItems::BestBefore).date())
+                    .col(ColumnDef::new(PantryItems::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(PantryItems::UpdatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PantryItems::Table, PantryItems::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PantryItems::Table, PantryItems::IngredientId)
+                            .to(Ingredients::Table, Ingredients::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PantryItems::Table, PantryItems::UnitId)
+                            .to(IngredientUnits::Table, IngredientUnits::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("pantry_items_user_id_ingredient_id_idx")
+                    .table(PantryItems::Table)
+                    .col(PantryItems::UserId)
+                    .col(PantryItems::IngredientId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PantryItems::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum PantryItems {
+    Table,
+    Id,
+    UserId,
+    IngredientId,
+    UnitId,
+    Amount,
+    BestBefore,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Ingredients {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum IngredientUnits {
+    Table,
+    Id,
+}
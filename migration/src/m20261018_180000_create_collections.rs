@@ -0,0 +1,195 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Collections::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Collections::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Collections::OwnerId).big_integer().not_null())
+                    .col(ColumnDef::new(Collections::Name).string().not_null())
+                    .col(ColumnDef::new(Collections::Description).text())
+                    .col(ColumnDef::new(Collections::Image).string())
+                    .col(ColumnDef::new(Collections::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(Collections::UpdatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Collections::Table, Collections::OwnerId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("collections_owner_id_idx")
+                    .table(Collections::Table)
+                    .col(Collections::OwnerId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionsRecipes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CollectionsRecipes::CollectionId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CollectionsRecipes::RecipeId).big_integer().not_null())
+                    .col(ColumnDef::new(CollectionsRecipes::Position).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(CollectionsRecipes::CollectionId)
+                            .col(CollectionsRecipes::RecipeId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CollectionsRecipes::Table, CollectionsRecipes::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CollectionsRecipes::Table, CollectionsRecipes::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionCollaborators::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CollectionCollaborators::CollectionId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CollectionCollaborators::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(CollectionCollaborators::Role).string_len(16).not_null())
+                    .col(
+                        ColumnDef::new(CollectionCollaborators::InsertedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionCollaborators::UpdatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(CollectionCollaborators::CollectionId)
+                            .col(CollectionCollaborators::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CollectionCollaborators::Table, CollectionCollaborators::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CollectionCollaborators::Table, CollectionCollaborators::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("collection_collaborators_user_id_idx")
+                    .table(CollectionCollaborators::Table)
+                    .col(CollectionCollaborators::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CollectionCollaborators::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CollectionsRecipes::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Collections::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Collections {
+    Table,
+    Id,
+    OwnerId,
+    Name,
+    Description,
+    Image,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum CollectionsRecipes {
+    Table,
+    CollectionId,
+    RecipeId,
+    Position,
+}
+
+#[derive(Iden)]
+enum CollectionCollaborators {
+    Table,
+    CollectionId,
+    UserId,
+    Role,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Recipes {
+    Table,
+    Id,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecipeRevisions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecipeRevisions::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RecipeRevisions::RecipeId).big_integer().not_null())
+                    .col(ColumnDef::new(RecipeRevisions::Snapshot).json_binary().not_null())
+                    .col(ColumnDef::new(RecipeRevisions::InsertedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(RecipeRevisions::Table, RecipeRevisions::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("recipe_revisions_recipe_id_idx")
+                    .table(RecipeRevisions::Table)
+                    .col(RecipeRevisions::RecipeId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecipeRevisions::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum RecipeRevisions {
+    Table,
+    Id,
+    RecipeId,
+    Snapshot,
+    InsertedAt,
+}
+
+#[derive(Iden)]
+enum Recipes {
+    Table,
+    Id,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .add_column(ColumnDef::new(Recipes::ForkedFromId).big_integer())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("recipes_forked_from_id_fkey")
+                            .from_tbl(Recipes::Table)
+                            .from_col(Recipes::ForkedFromId)
+                            .to_tbl(Recipes::Table)
+                            .to_col(Recipes::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("recipes_forked_from_id_idx")
+                    .table(Recipes::Table)
+                    .col(Recipes::ForkedFromId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .drop_column(Recipes::ForkedFromId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Recipes {
+    Table,
+    Id,
+    ForkedFromId,
+}
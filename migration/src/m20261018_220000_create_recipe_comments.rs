@@ -0,0 +1,103 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecipeComments::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecipeComments::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RecipeComments::RecipeId).big_integer().not_null())
+                    .col(ColumnDef::new(RecipeComments::AuthorId).big_integer().not_null())
+                    .col(ColumnDef::new(RecipeComments::ParentId).big_integer())
+                    .col(ColumnDef::new(RecipeComments::Body).text().not_null())
+                    .col(ColumnDef::new(RecipeComments::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(RecipeComments::EditedAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(RecipeComments::Table, RecipeComments::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(RecipeComments::Table, RecipeComments::AuthorId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(RecipeComments::Table, RecipeComments::ParentId)
+                            .to(RecipeComments::Table, RecipeComments::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("recipe_comments_recipe_id_idx")
+                    .table(RecipeComments::Table)
+                    .col(RecipeComments::RecipeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("recipe_comments_parent_id_idx")
+                    .table(RecipeComments::Table)
+                    .col(RecipeComments::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecipeComments::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum RecipeComments {
+    Table,
+    Id,
+    RecipeId,
+    AuthorId,
+    ParentId,
+    Body,
+    InsertedAt,
+    EditedAt,
+}
+
+#[derive(Iden)]
+enum Recipes {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Favorites::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Favorites::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(Favorites::RecipeId).big_integer().not_null())
+                    .col(ColumnDef::new(Favorites::InsertedAt).timestamp().not_null())
+                    .primary_key(Index::create().col(Favorites::UserId).col(Favorites::RecipeId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Favorites::Table, Favorites::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Favorites::Table, Favorites::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("favorites_recipe_id_idx")
+                    .table(Favorites::Table)
+                    .col(Favorites::RecipeId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Favorites::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Favorites {
+    Table,
+    UserId,
+    RecipeId,
+    InsertedAt,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Recipes {
+    Table,
+    Id,
+}
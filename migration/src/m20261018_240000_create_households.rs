@@ -0,0 +1,67 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Every existing user gets a household of their own, reusing the user's id, and their weekplans and shopping lists are
+// moved to it.
+const UP: &[&str] = &[
+    "CREATE TABLE households (
+        id bigserial PRIMARY KEY,
+        name varchar(255) NOT NULL,
+        invite_code varchar(64) UNIQUE,
+        inserted_at timestamp NOT NULL,
+        updated_at timestamp NOT NULL
+    )",
+    "INSERT INTO households (id, name, inserted_at, updated_at)
+        SELECT id, COALESCE(name, email), NOW() AT TIME ZONE 'UTC', NOW() AT TIME ZONE 'UTC' FROM users",
+    "SELECT setval(pg_get_serial_sequence('households', 'id'), COALESCE((SELECT MAX(id) FROM households), 0) + 1, \
+     false)",
+    "ALTER TABLE users ADD COLUMN household_id bigint REFERENCES households (id) ON UPDATE CASCADE",
+    "UPDATE users SET household_id = id",
+    "ALTER TABLE users ALTER COLUMN household_id SET NOT NULL",
+    "CREATE INDEX users_household_id_idx ON users (household_id)",
+    "ALTER TABLE weekplans ADD COLUMN household_id bigint REFERENCES households (id) ON DELETE CASCADE ON UPDATE \
+     CASCADE",
+    "UPDATE weekplans SET household_id = user_id",
+    "ALTER TABLE weekplans ALTER COLUMN household_id SET NOT NULL",
+    "CREATE INDEX weekplans_household_id_date_idx ON weekplans (household_id, date)",
+    "ALTER TABLE shopping_lists ADD COLUMN household_id bigint REFERENCES households (id) ON DELETE CASCADE ON UPDATE \
+     CASCADE",
+    "UPDATE shopping_lists SET household_id = user_id",
+    "ALTER TABLE shopping_lists ALTER COLUMN household_id SET NOT NULL",
+    "DROP INDEX IF EXISTS shopping_lists_user_id_week_unique",
+    "CREATE UNIQUE INDEX shopping_lists_household_id_week_unique ON shopping_lists (household_id, week)",
+];
+
+const DOWN: &[&str] = &[
+    "DROP INDEX IF EXISTS shopping_lists_household_id_week_unique",
+    "CREATE UNIQUE INDEX shopping_lists_user_id_week_unique ON shopping_lists (user_id, week)",
+    "ALTER TABLE shopping_lists DROP COLUMN IF EXISTS household_id",
+    "ALTER TABLE weekplans DROP COLUMN IF EXISTS household_id",
+    "ALTER TABLE users DROP COLUMN IF EXISTS household_id",
+    "DROP TABLE IF EXISTS households",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for sql in UP {
+            let stmt = Statement::from_string(manager.get_database_backend(), (*sql).to_owned());
+            manager.get_connection().execute(stmt).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for sql in DOWN {
+            let stmt = Statement::from_string(manager.get_database_backend(), (*sql).to_owned());
+            manager.get_connection().execute(stmt).await?;
+        }
+
+        Ok(())
+    }
+}
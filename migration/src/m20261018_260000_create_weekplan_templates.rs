@@ -0,0 +1,148 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WeekplanTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WeekplanTemplates::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WeekplanTemplates::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(WeekplanTemplates::Name).string().not_null())
+                    .col(ColumnDef::new(WeekplanTemplates::InsertedAt).timestamp().not_null())
+                    .col(ColumnDef::new(WeekplanTemplates::UpdatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(WeekplanTemplates::Table, WeekplanTemplates::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("weekplan_templates_user_id_idx")
+                    .table(WeekplanTemplates::Table)
+                    .col(WeekplanTemplates::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WeekplanTemplateEntries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WeekplanTemplateEntries::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WeekplanTemplateEntries::TemplateId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WeekplanTemplateEntries::Weekday).integer().not_null())
+                    .col(
+                        ColumnDef::new(WeekplanTemplateEntries::MealSlot)
+                            .string_len(16)
+                            .not_null()
+                            .default("dinner"),
+                    )
+                    .col(
+                        ColumnDef::new(WeekplanTemplateEntries::RecipeId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WeekplanTemplateEntries::Portions).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(WeekplanTemplateEntries::Table, WeekplanTemplateEntries::TemplateId)
+                            .to(WeekplanTemplates::Table, WeekplanTemplates::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(WeekplanTemplateEntries::Table, WeekplanTemplateEntries::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("weekplan_template_entries_template_id_idx")
+                    .table(WeekplanTemplateEntries::Table)
+                    .col(WeekplanTemplateEntries::TemplateId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WeekplanTemplateEntries::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WeekplanTemplates::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum WeekplanTemplates {
+    Table,
+    Id,
+    UserId,
+    Name,
+    InsertedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum WeekplanTemplateEntries {
+    Table,
+    Id,
+    TemplateId,
+    Weekday,
+    MealSlot,
+    RecipeId,
+    Portions,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Recipes {
+    Table,
+    Id,
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Weekplans::Table)
+                    .add_column(ColumnDef::new(Weekplans::LeftoversOfId).big_integer().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("weekplans_leftovers_of_id_fkey")
+                            .from_tbl(Weekplans::Table)
+                            .from_col(Weekplans::LeftoversOfId)
+                            .to_tbl(Weekplans::Table)
+                            .to_col(Weekplans::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Weekplans::Table)
+                    .drop_column(Weekplans::LeftoversOfId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Weekplans {
+    Table,
+    Id,
+    LeftoversOfId,
+}
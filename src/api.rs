@@ -1,9 +1,18 @@
 use async_graphql::{dataloader::DataLoader, extensions::Logger, *};
 use sea_orm::DatabaseConnection;
 
+mod collections;
+mod cook_log;
+mod favorites;
+mod households;
 mod ingredients;
+mod nutrition;
+mod pantry;
+mod recipe_comments;
+mod recipe_revisions;
 mod recipes;
 mod session;
+mod shopping_lists;
 mod steps;
 mod tags;
 mod users;
@@ -18,6 +27,15 @@ pub struct MutationRoot(
     users::UsersMutations,
     steps::StepsMutations,
     weekplans::WeekplansMutations,
+    shopping_lists::ShoppingListsMutations,
+    cook_log::CookLogMutations,
+    nutrition::NutritionMutations,
+    pantry::PantryMutations,
+    collections::CollectionsMutations,
+    recipe_revisions::RecipeRevisionsMutations,
+    recipe_comments::RecipeCommentsMutations,
+    favorites::FavoritesMutations,
+    households::HouseholdsMutations,
 );
 
 #[derive(async_graphql::MergedObject, Default)]
@@ -28,6 +46,15 @@ pub struct QueryRoot(
     users::UsersQueries,
     steps::StepsQueries,
     weekplans::WeekplansQueries,
+    shopping_lists::ShoppingListsQueries,
+    cook_log::CookLogQueries,
+    nutrition::NutritionQueries,
+    pantry::PantryQueries,
+    collections::CollectionsQueries,
+    recipe_revisions::RecipeRevisionsQueries,
+    recipe_comments::RecipeCommentsQueries,
+    favorites::FavoritesQueries,
+    households::HouseholdsQueries,
 );
 
 pub type RecipesSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
@@ -58,6 +85,26 @@ pub fn create_schema(db: DatabaseConnection) -> RecipesSchema {
             entity::weekplans::WeekplanLoader { conn: db.clone() },
             tokio::spawn,
         ))
+        .data(DataLoader::new(
+            entity::shopping_lists::ShoppingListLoader { conn: db.clone() },
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            entity::collections::CollectionsLoader { conn: db.clone() },
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            entity::recipe_comments::RecipeCommentsLoader { conn: db.clone() },
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            entity::households::HouseholdsLoader { conn: db.clone() },
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            entity::weekplan_templates::WeekplanTemplatesLoader { conn: db.clone() },
+            tokio::spawn,
+        ))
         .extension(Logger)
         .data(db)
         .finish()
@@ -0,0 +1,165 @@
+use async_graphql::*;
+use entity::collection_collaborators::{CollaboratorRole, Model as CollectionCollaborator};
+use entity::collections::Model as Collection;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::collections_policy::{CollectionActions, CollectionsPolicy};
+use crate::authorization::recipes_policy::RecipesPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::collections::{CollectionInput, CollectionWithRole};
+
+#[derive(Default)]
+pub struct CollectionsQueries;
+
+#[derive(Default)]
+pub struct CollectionsMutations;
+
+#[Object]
+impl CollectionsQueries {
+    /// The collections owned by or shared with the current user
+    async fn collections(&self, ctx: &Context<'_>, limit: u64, offset: u64) -> Result<Vec<Collection>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(CollectionsPolicy, CollectionActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::collections::list_collections(limit, offset, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn collection(&self, ctx: &Context<'_>, id: i64) -> Result<Option<Collection>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let collection = crate::collections::get_collection_with_role(id, user, db).await?;
+        authorized(CollectionsPolicy, CollectionActions::Get, user, collection.as_ref(), db)?;
+
+        Ok(collection.map(|collection| collection.collection))
+    }
+}
+
+async fn authorized_collection(
+    ctx: &Context<'_>,
+    action: CollectionActions,
+    id: i64,
+) -> Result<Option<CollectionWithRole>> {
+    let user = ctx.data_opt::<entity::users::Model>();
+    let db = ctx.data::<DatabaseConnection>()?;
+
+    let collection = crate::collections::get_collection_with_role(id, user, db).await?;
+    authorized(CollectionsPolicy, action, user, collection.as_ref(), db)?;
+
+    Ok(collection)
+}
+
+#[Object]
+impl CollectionsMutations {
+    async fn create_collection(&self, ctx: &Context<'_>, collection: CollectionInput) -> Result<Collection> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(CollectionsPolicy, CollectionActions::Create, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+        let file = collection.image.as_ref().map(|picture| picture.value(ctx).unwrap());
+
+        crate::collections::create_collection(collection, file, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn update_collection(&self, ctx: &Context<'_>, id: i64, collection: CollectionInput) -> Result<Collection> {
+        let db = ctx.data::<DatabaseConnection>()?;
+        authorized_collection(ctx, CollectionActions::Update, id).await?;
+
+        let file = collection.image.as_ref().map(|picture| picture.value(ctx).unwrap());
+
+        crate::collections::update_collection(id, collection, file, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn delete_collection(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let db = ctx.data::<DatabaseConnection>()?;
+        authorized_collection(ctx, CollectionActions::Delete, id).await?;
+
+        crate::collections::delete_collection(id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn add_recipe_to_collection(&self, ctx: &Context<'_>, id: i64, recipe_id: i64) -> Result<Collection> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+        authorized_collection(ctx, CollectionActions::Update, id).await?;
+
+        let recipe = crate::recipes::get_recipe_by_id(recipe_id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Recipe not found", Some(ctx.item.pos)))?;
+        authorized(RecipesPolicy, DefaultActions::Get, user, Some(&recipe), db)?;
+
+        crate::collections::add_recipe(id, recipe.id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn remove_recipe_from_collection(&self, ctx: &Context<'_>, id: i64, recipe_id: i64) -> Result<Collection> {
+        let db = ctx.data::<DatabaseConnection>()?;
+        authorized_collection(ctx, CollectionActions::Update, id).await?;
+
+        crate::collections::remove_recipe(id, recipe_id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Sets the order of the collection's recipes, `recipeIds` has to contain every recipe of the collection
+    async fn reorder_collection(&self, ctx: &Context<'_>, id: i64, recipe_ids: Vec<i64>) -> Result<Collection> {
+        let db = ctx.data::<DatabaseConnection>()?;
+        authorized_collection(ctx, CollectionActions::Update, id).await?;
+
+        crate::collections::reorder_recipes(id, recipe_ids, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Shares the collection with the user with the given email, or changes the user's role
+    async fn set_collection_collaborator(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        #[graphql(validator(email))] email: String,
+        role: CollaboratorRole,
+    ) -> Result<CollectionCollaborator> {
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        // due to policy check the collection is always Some
+        let collection = authorized_collection(ctx, CollectionActions::Share, id).await?.unwrap();
+
+        crate::collections::set_collaborator(&collection.collection, email, role, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Stops sharing the collection with the user; collaborators may remove themselves
+    async fn remove_collection_collaborator(&self, ctx: &Context<'_>, id: i64, user_id: i64) -> Result<bool> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let action = if user.is_some_and(|user| user.id == user_id) {
+            CollectionActions::Get
+        } else {
+            CollectionActions::Share
+        };
+
+        authorized_collection(ctx, action, id).await?;
+
+        crate::collections::remove_collaborator(id, user_id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
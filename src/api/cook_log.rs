@@ -0,0 +1,112 @@
+use async_graphql::*;
+use entity::cook_log::Model as CookLogEntry;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::cook_log_policy::CookLogPolicy;
+use crate::authorization::recipes_policy::RecipesPolicy;
+use crate::authorization::weekplan_policy::WeekplanPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::cook_log::CookLogInput;
+
+#[derive(Default)]
+pub struct CookLogQueries;
+
+#[derive(Default)]
+pub struct CookLogMutations;
+
+#[Object]
+impl CookLogQueries {
+    async fn cook_log(
+        &self,
+        ctx: &Context<'_>,
+        recipe_id: Option<i64>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<CookLogEntry>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(CookLogPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::cook_log::list_cook_log(recipe_id, limit, offset, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[Object]
+impl CookLogMutations {
+    async fn log_cooking(&self, ctx: &Context<'_>, recipe_id: i64, entry: CookLogInput) -> Result<CookLogEntry> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(CookLogPolicy, DefaultActions::Create, user, None, db)?;
+
+        let recipe = crate::recipes::get_recipe_by_id(recipe_id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Recipe not found", Some(ctx.item.pos)))?;
+        authorized(RecipesPolicy, DefaultActions::Get, user, Some(&recipe), db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::cook_log::log_cooking(recipe.id, entry, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Logs that the recipe of a weekplan entry has been cooked. Unless `deductFromPantry` is false the recipe's
+    /// ingredients are deducted from the pantry when the entry is logged for the first time.
+    async fn log_weekplan_cooking(
+        &self,
+        ctx: &Context<'_>,
+        weekplan_id: i64,
+        entry: CookLogInput,
+        #[graphql(default = true)] deduct_from_pantry: bool,
+    ) -> Result<CookLogEntry> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(CookLogPolicy, DefaultActions::Create, user, None, db)?;
+
+        let weekplan = crate::weekplan::get_weekplan_by_id(weekplan_id, db).await?;
+        authorized(WeekplanPolicy, DefaultActions::Update, user, weekplan.as_ref(), db)?;
+
+        // due to policy checks both are Some
+        let (user, weekplan) = (user.unwrap(), weekplan.unwrap());
+
+        crate::cook_log::log_weekplan_cooking(weekplan, entry, deduct_from_pantry, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn update_cook_log_entry(&self, ctx: &Context<'_>, id: i64, entry: CookLogInput) -> Result<CookLogEntry> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let existing_entry = crate::cook_log::get_cook_log_entry_by_id(id, db).await?;
+        authorized(CookLogPolicy, DefaultActions::Update, user, existing_entry.as_ref(), db)?;
+
+        // due to policy check the entry is a Some
+        let existing_entry = existing_entry.unwrap();
+
+        crate::cook_log::update_cook_log_entry(existing_entry, entry, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn delete_cook_log_entry(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let entry = crate::cook_log::get_cook_log_entry_by_id(id, db).await?;
+        authorized(CookLogPolicy, DefaultActions::Delete, user, entry.as_ref(), db)?;
+
+        crate::cook_log::delete_cook_log_entry(id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
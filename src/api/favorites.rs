@@ -0,0 +1,75 @@
+use async_graphql::*;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::favorites_policy::FavoritesPolicy;
+use crate::authorization::recipes_policy::RecipesPolicy;
+use crate::authorization::{DefaultActions, authorized};
+
+#[derive(Default)]
+pub struct FavoritesQueries;
+
+#[derive(Default)]
+pub struct FavoritesMutations;
+
+#[Object]
+impl FavoritesQueries {
+    /// The current user's favourites, the most recently added first
+    async fn favorite_recipes(
+        &self,
+        ctx: &Context<'_>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<entity::recipes::Model>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(FavoritesPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::favorites::list_favorite_recipes(limit, offset, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[Object]
+impl FavoritesMutations {
+    async fn favorite_recipe(&self, ctx: &Context<'_>, id: i64) -> Result<entity::recipes::Model> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(FavoritesPolicy, DefaultActions::Create, user, None, db)?;
+
+        let recipe = crate::recipes::get_recipe_by_id(id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Recipe not found", Some(ctx.item.pos)))?;
+        authorized(RecipesPolicy, DefaultActions::Get, user, Some(&recipe), db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::favorites::favorite_recipe(recipe, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn unfavorite_recipe(&self, ctx: &Context<'_>, id: i64) -> Result<entity::recipes::Model> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(FavoritesPolicy, DefaultActions::Create, user, None, db)?;
+
+        let recipe = crate::recipes::get_recipe_by_id(id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Recipe not found", Some(ctx.item.pos)))?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::favorites::unfavorite_recipe(recipe, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
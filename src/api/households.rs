@@ -0,0 +1,90 @@
+use async_graphql::*;
+use entity::households::Model as Household;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::household_policy::HouseholdPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::households::HouseholdInput;
+
+#[derive(Default)]
+pub struct HouseholdsQueries;
+
+#[derive(Default)]
+pub struct HouseholdsMutations;
+
+/// Loads the current user's household and checks the action on it
+async fn authorized_household(ctx: &Context<'_>, action: DefaultActions) -> Result<Household> {
+    let user = ctx.data_opt::<entity::users::Model>();
+    let db = ctx.data::<DatabaseConnection>()?;
+
+    authorized(HouseholdPolicy, DefaultActions::List, user, None, db)?;
+
+    // due to policy check user is always Some
+    let household = crate::households::get_household_by_id(user.unwrap().household_id, db)
+        .await?
+        .ok_or_else(|| ServerError::new("Household not found", Some(ctx.item.pos)))?;
+    authorized(HouseholdPolicy, action, user, Some(&household), db)?;
+
+    Ok(household)
+}
+
+#[Object]
+impl HouseholdsQueries {
+    /// The household of the current user; its members share the weekplan and the shopping lists
+    async fn household(&self, ctx: &Context<'_>) -> Result<Household> {
+        authorized_household(ctx, DefaultActions::Get).await
+    }
+}
+
+#[Object]
+impl HouseholdsMutations {
+    async fn update_household(&self, ctx: &Context<'_>, household: HouseholdInput) -> Result<Household> {
+        let db = ctx.data::<DatabaseConnection>()?;
+        let existing = authorized_household(ctx, DefaultActions::Update).await?;
+
+        crate::households::update_household(existing.id, household, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Creates a new invite code for the household, the previous code stops working
+    async fn create_household_invite(&self, ctx: &Context<'_>) -> Result<Household> {
+        let db = ctx.data::<DatabaseConnection>()?;
+        let household = authorized_household(ctx, DefaultActions::Update).await?;
+
+        crate::households::create_invite_code(household.id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Joins the household with the given invite code. When the current user was the last member of their household
+    /// its weekplan and shopping lists are taken along.
+    async fn join_household(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(max_length = 64))] invite_code: String,
+    ) -> Result<Household> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(HouseholdPolicy, DefaultActions::Create, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::households::join_household(invite_code, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Removes a member from the household, members may remove themselves to leave it. The removed member gets a new
+    /// household of their own.
+    async fn remove_household_member(&self, ctx: &Context<'_>, user_id: i64) -> Result<Household> {
+        let db = ctx.data::<DatabaseConnection>()?;
+        let household = authorized_household(ctx, DefaultActions::Update).await?;
+
+        crate::households::remove_member(&household, user_id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
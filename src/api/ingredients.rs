@@ -2,6 +2,7 @@ use async_graphql::*;
 use sea_orm::DatabaseConnection;
 
 use crate::authorization::{authorized, ingredients_policy::IngredientsPolicy, DefaultActions};
+use crate::ingredient_parser::ParsedIngredientLine;
 use crate::ingredients::IngredientInput;
 
 #[derive(Default)]
@@ -53,6 +54,45 @@ impl IngredientsQueries {
 
         Ok(ingredient)
     }
+
+    /// Converts an amount of an ingredient between its units and g/ml. Without a target the amount is converted to
+    /// the ingredient's reference.
+    async fn convert_amount(
+        &self,
+        ctx: &Context<'_>,
+        ingredient_id: i64,
+        amount: f64,
+        unit_id: Option<i64>,
+        target_unit_id: Option<i64>,
+        target_reference: Option<entity::ingredients::Reference>,
+    ) -> Result<Option<f64>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let ingredient = crate::ingredients::get_ingredient_by_id(ingredient_id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Ingredient not found", Some(ctx.item.pos)))?;
+        authorized(IngredientsPolicy, DefaultActions::Get, user, Some(&ingredient), db)?;
+
+        crate::ingredients::convert_amount(&ingredient, amount, unit_id, target_unit_id, target_reference, db).await
+    }
+
+    /// Parses free-text ingredient lines like "200 g Mehl" into amount, unit, annotation and candidate ingredients
+    async fn parse_ingredient_lines(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(max_items = 100))] lines: Vec<String>,
+        #[graphql(default = 3, validator(minimum = 1, maximum = 10))] limit: usize,
+    ) -> Result<Vec<ParsedIngredientLine>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(IngredientsPolicy, DefaultActions::List, user, None, db)?;
+
+        crate::ingredient_parser::parse_ingredient_lines(&lines, limit, db)
+            .await
+            .map_err(|e| e.into())
+    }
 }
 
 #[Object]
@@ -0,0 +1,74 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use entity::nutrition_targets::Model as NutritionTargets;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::nutrition_targets_policy::NutritionTargetsPolicy;
+use crate::authorization::weekplan_policy::WeekplanPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::nutrition::{NutritionTargetsInput, WeekplanNutrition};
+
+#[derive(Default)]
+pub struct NutritionQueries;
+
+#[derive(Default)]
+pub struct NutritionMutations;
+
+#[Object]
+impl NutritionQueries {
+    async fn nutrition_targets(&self, ctx: &Context<'_>) -> Result<Option<NutritionTargets>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(NutritionTargetsPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::nutrition::get_nutrition_targets(user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Nutrition values of the week's entries summed up per day, with flags for days deviating from the user's
+    /// targets by more than `tolerance` (a fraction, defaults to 0.2)
+    async fn weekplan_nutrition(
+        &self,
+        ctx: &Context<'_>,
+        week: NaiveDate,
+        #[graphql(validator(minimum = 0))] tolerance: Option<f64>,
+    ) -> Result<WeekplanNutrition> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(WeekplanPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::nutrition::weekplan_nutrition(&week, tolerance, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[Object]
+impl NutritionMutations {
+    async fn set_nutrition_targets(
+        &self,
+        ctx: &Context<'_>,
+        targets: NutritionTargetsInput,
+    ) -> Result<NutritionTargets> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(NutritionTargetsPolicy, DefaultActions::Create, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::nutrition::set_nutrition_targets(targets, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
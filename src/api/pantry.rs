@@ -0,0 +1,77 @@
+use async_graphql::*;
+use entity::pantry_items::Model as PantryItem;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::pantry_items_policy::PantryItemsPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::pantry::PantryItemInput;
+
+#[derive(Default)]
+pub struct PantryQueries;
+
+#[derive(Default)]
+pub struct PantryMutations;
+
+#[Object]
+impl PantryQueries {
+    async fn pantry_items(&self, ctx: &Context<'_>) -> Result<Vec<PantryItem>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(PantryItemsPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::pantry::list_pantry_items(user, db).await.map_err(|e| e.into())
+    }
+
+    async fn pantry_item(&self, ctx: &Context<'_>, id: i64) -> Result<Option<PantryItem>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let item = crate::pantry::get_pantry_item_by_id(id, db).await?;
+        authorized(PantryItemsPolicy, DefaultActions::Get, user, item.as_ref(), db)?;
+
+        Ok(item)
+    }
+}
+
+#[Object]
+impl PantryMutations {
+    async fn create_pantry_item(&self, ctx: &Context<'_>, item: PantryItemInput) -> Result<PantryItem> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(PantryItemsPolicy, DefaultActions::Create, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::pantry::create_pantry_item(item, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn update_pantry_item(&self, ctx: &Context<'_>, id: i64, item: PantryItemInput) -> Result<PantryItem> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let existing_item = crate::pantry::get_pantry_item_by_id(id, db).await?;
+        authorized(PantryItemsPolicy, DefaultActions::Update, user, existing_item.as_ref(), db)?;
+
+        crate::pantry::update_pantry_item(id, item, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn delete_pantry_item(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let item = crate::pantry::get_pantry_item_by_id(id, db).await?;
+        authorized(PantryItemsPolicy, DefaultActions::Delete, user, item.as_ref(), db)?;
+
+        crate::pantry::delete_pantry_item(id, db).await.map_err(|e| e.into())
+    }
+}
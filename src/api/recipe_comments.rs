@@ -0,0 +1,105 @@
+use async_graphql::*;
+use entity::recipe_comments::Model as RecipeComment;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::comments_policy::CommentsPolicy;
+use crate::authorization::recipes_policy::RecipesPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::recipe_comments::RecipeCommentInput;
+
+#[derive(Default)]
+pub struct RecipeCommentsQueries;
+
+#[derive(Default)]
+pub struct RecipeCommentsMutations;
+
+/// Loads the recipe and checks that the current user may see it
+async fn authorized_recipe(ctx: &Context<'_>, recipe_id: i64) -> Result<entity::recipes::Model> {
+    let user = ctx.data_opt::<entity::users::Model>();
+    let db = ctx.data::<DatabaseConnection>()?;
+
+    let recipe = crate::recipes::get_recipe_by_id(recipe_id, db)
+        .await?
+        .ok_or_else(|| ServerError::new("Recipe not found", Some(ctx.item.pos)))?;
+    authorized(RecipesPolicy, DefaultActions::Get, user, Some(&recipe), db)?;
+
+    Ok(recipe)
+}
+
+#[Object]
+impl RecipeCommentsQueries {
+    /// The comments of a recipe, oldest first. Without `parentId` only the top level comments are returned,
+    /// otherwise the replies to the given comment.
+    async fn recipe_comments(
+        &self,
+        ctx: &Context<'_>,
+        recipe_id: i64,
+        parent_id: Option<i64>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<RecipeComment>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(CommentsPolicy, DefaultActions::List, user, None, db)?;
+        let recipe = authorized_recipe(ctx, recipe_id).await?;
+
+        crate::recipe_comments::list_comments(recipe.id, parent_id, limit, offset, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[Object]
+impl RecipeCommentsMutations {
+    async fn create_recipe_comment(
+        &self,
+        ctx: &Context<'_>,
+        recipe_id: i64,
+        parent_id: Option<i64>,
+        comment: RecipeCommentInput,
+    ) -> Result<RecipeComment> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(CommentsPolicy, DefaultActions::Create, user, None, db)?;
+        let recipe = authorized_recipe(ctx, recipe_id).await?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::recipe_comments::create_comment(recipe.id, parent_id, comment, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn update_recipe_comment(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        comment: RecipeCommentInput,
+    ) -> Result<RecipeComment> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let existing_comment = crate::recipe_comments::get_comment_by_id(id, db).await?;
+        authorized(CommentsPolicy, DefaultActions::Update, user, existing_comment.as_ref(), db)?;
+
+        crate::recipe_comments::update_comment(id, comment, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Deletes the comment and all replies to it
+    async fn delete_recipe_comment(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let comment = crate::recipe_comments::get_comment_by_id(id, db).await?;
+        authorized(CommentsPolicy, DefaultActions::Delete, user, comment.as_ref(), db)?;
+
+        crate::recipe_comments::delete_comment(id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
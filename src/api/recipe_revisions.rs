@@ -0,0 +1,62 @@
+use async_graphql::*;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::recipes_policy::RecipesPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::recipe_revisions::RecipeDiff;
+
+#[derive(Default)]
+pub struct RecipeRevisionsQueries;
+
+#[derive(Default)]
+pub struct RecipeRevisionsMutations;
+
+#[Object]
+impl RecipeRevisionsQueries {
+    /// Changes between two revisions of a recipe; compares with the current state of the recipe when `toId` is empty
+    async fn recipe_revision_diff(&self, ctx: &Context<'_>, from_id: i64, to_id: Option<i64>) -> Result<RecipeDiff> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let from = crate::recipe_revisions::get_revision_by_id(from_id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Revision not found", Some(ctx.item.pos)))?;
+
+        let to = match to_id {
+            Some(to_id) => Some(
+                crate::recipe_revisions::get_revision_by_id(to_id, db)
+                    .await?
+                    .filter(|to| to.recipe_id == from.recipe_id)
+                    .ok_or_else(|| ServerError::new("Revision not found", Some(ctx.item.pos)))?,
+            ),
+            None => None,
+        };
+
+        let recipe = crate::recipes::get_recipe_by_id(from.recipe_id, db).await?;
+        authorized(RecipesPolicy, DefaultActions::Get, user, recipe.as_ref(), db)?;
+
+        crate::recipe_revisions::diff_revisions(&from, to.as_ref(), db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[Object]
+impl RecipeRevisionsMutations {
+    /// Resets the recipe to the state of the revision; the state before is stored as a new revision
+    async fn restore_recipe_revision(&self, ctx: &Context<'_>, id: i64) -> Result<entity::recipes::Model> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let revision = crate::recipe_revisions::get_revision_by_id(id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Revision not found", Some(ctx.item.pos)))?;
+
+        let recipe = crate::recipes::get_recipe_by_id(revision.recipe_id, db).await?;
+        authorized(RecipesPolicy, DefaultActions::Update, user, recipe.as_ref(), db)?;
+
+        crate::recipe_revisions::restore_revision(revision, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
@@ -1,8 +1,13 @@
 use async_graphql::*;
 use sea_orm::DatabaseConnection;
 
+use crate::authorization::pantry_items_policy::PantryItemsPolicy;
 use crate::authorization::{authorized, recipes_policy::RecipesPolicy, DefaultActions};
-use crate::recipes::RecipeInput;
+use crate::jsonld::ImportedRecipe;
+use crate::recipes::{
+    CookLogFilter, DietaryFilter, RecipeIngredientsMatch, RecipeInput, RecipeListOptions, RecipeOrder,
+    RecipeSearchHit, SearchMode, SortDirection,
+};
 
 #[derive(Default)]
 pub struct RecipesQueries;
@@ -12,6 +17,7 @@ pub struct RecipesMutations;
 
 #[Object]
 impl RecipesQueries {
+    #[allow(clippy::too_many_arguments)]
     async fn recipes(
         &self,
         ctx: &Context<'_>,
@@ -19,15 +25,33 @@ impl RecipesQueries {
         #[graphql(validator(max_items = 3))] tags: Option<Vec<String>>,
         limit: u64,
         offset: u64,
+        #[graphql(default)] mode: SearchMode,
+        #[graphql(default)] order: RecipeOrder,
+        direction: Option<SortDirection>,
+        cook_log: Option<CookLogFilter>,
+        dietary: Option<DietaryFilter>,
     ) -> Result<Vec<entity::recipes::Model>> {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
 
         authorized(RecipesPolicy, DefaultActions::List, user, None, db)?;
 
+        let options = RecipeListOptions {
+            order,
+            direction,
+            cook_log,
+            dietary,
+            user_id: user.map(|user| user.id),
+        };
+
+        if let (SearchMode::Fulltext, Some(search)) = (mode, &search) {
+            let hits = crate::recipes::search_recipes(limit, offset, search, tags, &options, db).await?;
+            return Ok(hits.into_iter().map(|hit| hit.recipe).collect());
+        }
+
         let search = search.map(|s| s.split_whitespace().map(|s| s.to_lowercase()).collect());
 
-        crate::recipes::list_recipes(limit, offset, search, tags, db)
+        crate::recipes::list_recipes(limit, offset, search, tags, &options, db)
             .await
             .map_err(|e| e.into())
     }
@@ -37,19 +61,95 @@ impl RecipesQueries {
         ctx: &Context<'_>,
         search: Option<String>,
         tags: Option<Vec<String>>,
+        #[graphql(default)] mode: SearchMode,
+        cook_log: Option<CookLogFilter>,
+        dietary: Option<DietaryFilter>,
     ) -> Result<u64> {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
 
         authorized(RecipesPolicy, DefaultActions::List, user, None, db)?;
 
+        let options = RecipeListOptions {
+            cook_log,
+            dietary,
+            user_id: user.map(|user| user.id),
+            ..Default::default()
+        };
+
+        if let (SearchMode::Fulltext, Some(search)) = (mode, &search) {
+            return crate::recipes::count_search_recipes(search, tags, &options, db)
+                .await
+                .map_err(|e| e.into());
+        }
+
         let search = search.map(|s| s.split_whitespace().map(|s| s.to_lowercase()).collect());
 
-        crate::recipes::count_recipes(search, tags, db)
+        crate::recipes::count_recipes(search, tags, &options, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn search_recipes(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(min_length = 1, max_length = 255))] search: String,
+        #[graphql(validator(max_items = 3))] tags: Option<Vec<String>>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<RecipeSearchHit>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(RecipesPolicy, DefaultActions::List, user, None, db)?;
+
+        let options = RecipeListOptions {
+            user_id: user.map(|user| user.id),
+            ..Default::default()
+        };
+
+        crate::recipes::search_recipes(limit, offset, &search, tags, &options, db)
             .await
             .map_err(|e| e.into())
     }
 
+    /// Recipes which can be cooked with the given ingredients (or the ones in the user's pantry when omitted) and at
+    /// most `maxMissing` additional ones, the recipes missing the fewest ingredients first
+    async fn recipes_by_ingredients(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(max_items = 100))] ingredient_ids: Option<Vec<i64>>,
+        #[graphql(default)] max_missing: u64,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<RecipeIngredientsMatch>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(RecipesPolicy, DefaultActions::List, user, None, db)?;
+
+        let ingredient_ids = match ingredient_ids {
+            Some(ingredient_ids) => ingredient_ids,
+            None => {
+                authorized(PantryItemsPolicy, DefaultActions::List, user, None, db)?;
+
+                // due to policy check user is always Some
+                crate::pantry::pantry_ingredient_ids(user.unwrap(), db).await?
+            }
+        };
+
+        crate::recipes::recipes_by_ingredients(
+            &ingredient_ids,
+            max_missing,
+            limit,
+            offset,
+            user.map(|user| user.id),
+            db,
+        )
+        .await
+        .map_err(|e| e.into())
+    }
+
     async fn recipe(&self, ctx: &Context<'_>, id: i64) -> Result<Option<entity::recipes::Model>> {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
@@ -65,7 +165,7 @@ impl RecipesQueries {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
 
-        let recipe = crate::recipes::get_random_recipe(db).await?;
+        let recipe = crate::recipes::get_random_recipe(user.map(|user| user.id), db).await?;
 
         authorized(RecipesPolicy, DefaultActions::Get, user, recipe.as_ref(), db)?;
 
@@ -77,15 +177,22 @@ impl RecipesQueries {
         ctx: &Context<'_>,
         limit: u64,
         tags: Option<Vec<String>>,
+        dietary: Option<DietaryFilter>,
     ) -> Result<Vec<entity::recipes::Model>> {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
 
         authorized(RecipesPolicy, DefaultActions::List, user, None, db)?;
 
-        crate::recipes::get_random_recipes(limit, tags.unwrap_or_default(), db)
-            .await
-            .map_err(|e| e.into())
+        crate::recipes::get_random_recipes(
+            limit,
+            tags.unwrap_or_default(),
+            dietary.as_ref(),
+            user.map(|user| user.id),
+            db,
+        )
+        .await
+        .map_err(|e| e.into())
     }
 }
 
@@ -125,6 +232,49 @@ impl RecipesMutations {
             .map_err(|e| e.into())
     }
 
+    /// Imports a schema.org Recipe JSON-LD document, either pasted as `document` or uploaded as `file`
+    async fn import_recipe(
+        &self,
+        ctx: &Context<'_>,
+        document: Option<String>,
+        file: Option<Upload>,
+    ) -> Result<ImportedRecipe> {
+        let user = ctx.data::<entity::users::Model>()?;
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(RecipesPolicy, DefaultActions::Create, Some(user), None, db)?;
+
+        let document = match (document, file) {
+            (Some(document), _) => document,
+            (None, Some(file)) => crate::jsonld::read_upload(file.value(ctx)?)?,
+            (None, None) => return Err(Error::new("either document or file is required")),
+        };
+
+        crate::jsonld::import_recipe(&document, user.id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Copies the recipe into a new recipe owned by the current user, e.g. to create a variant of it
+    async fn fork_recipe(&self, ctx: &Context<'_>, id: i64) -> Result<entity::recipes::Model> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(RecipesPolicy, DefaultActions::Create, user, None, db)?;
+
+        let recipe = crate::recipes::get_recipe_by_id(id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Recipe not found", Some(ctx.item.pos)))?;
+        authorized(RecipesPolicy, DefaultActions::Get, user, Some(&recipe), db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::recipes::fork_recipe(recipe, user.id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
     async fn delete_recipe(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
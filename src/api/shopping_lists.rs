@@ -0,0 +1,172 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use entity::shopping_list_items::Model as ShoppingListItem;
+use entity::shopping_lists::Model as ShoppingList;
+use sea_orm::DatabaseConnection;
+
+use crate::authorization::shopping_list_policy::ShoppingListPolicy;
+use crate::authorization::{DefaultActions, authorized};
+use crate::shopping_lists::ShoppingListItemInput;
+
+#[derive(Default)]
+pub struct ShoppingListsQueries;
+
+#[derive(Default)]
+pub struct ShoppingListsMutations;
+
+#[Object]
+impl ShoppingListsQueries {
+    async fn shopping_lists(&self, ctx: &Context<'_>, limit: u64, offset: u64) -> Result<Vec<ShoppingList>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(ShoppingListPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::shopping_lists::list_shopping_lists(limit, offset, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn shopping_list(&self, ctx: &Context<'_>, id: i64) -> Result<Option<ShoppingList>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let list = crate::shopping_lists::get_shopping_list_by_id(id, db).await?;
+        authorized(ShoppingListPolicy, DefaultActions::Get, user, list.as_ref(), db)?;
+
+        Ok(list)
+    }
+
+    async fn shopping_list_for_week(&self, ctx: &Context<'_>, week: NaiveDate) -> Result<Option<ShoppingList>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(ShoppingListPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::shopping_lists::get_shopping_list_for_week(&week, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[Object]
+impl ShoppingListsMutations {
+    async fn generate_shopping_list(
+        &self,
+        ctx: &Context<'_>,
+        week: NaiveDate,
+        #[graphql(validator(chars_min_length = 1, chars_max_length = 255))] name: Option<String>,
+    ) -> Result<ShoppingList> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(ShoppingListPolicy, DefaultActions::Create, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::shopping_lists::generate_shopping_list(week, name, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn regenerate_shopping_list(&self, ctx: &Context<'_>, id: i64) -> Result<ShoppingList> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let list = crate::shopping_lists::get_shopping_list_by_id(id, db).await?;
+        authorized(ShoppingListPolicy, DefaultActions::Update, user, list.as_ref(), db)?;
+
+        // due to policy check both are Some
+        let (user, list) = (user.unwrap(), list.unwrap());
+
+        crate::shopping_lists::regenerate_shopping_list(list, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn delete_shopping_list(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let list = crate::shopping_lists::get_shopping_list_by_id(id, db).await?;
+        authorized(ShoppingListPolicy, DefaultActions::Delete, user, list.as_ref(), db)?;
+
+        crate::shopping_lists::delete_shopping_list(id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn add_shopping_list_item(
+        &self,
+        ctx: &Context<'_>,
+        shopping_list_id: i64,
+        item: ShoppingListItemInput,
+    ) -> Result<ShoppingListItem> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let list = crate::shopping_lists::get_shopping_list_by_id(shopping_list_id, db).await?;
+        authorized(ShoppingListPolicy, DefaultActions::Update, user, list.as_ref(), db)?;
+
+        crate::shopping_lists::add_shopping_list_item(shopping_list_id, item, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn update_shopping_list_item(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        item: ShoppingListItemInput,
+    ) -> Result<ShoppingListItem> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let existing_item = crate::shopping_lists::get_shopping_list_item_by_id(id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Shopping list item not found", Some(ctx.item.pos)))?;
+        let list = crate::shopping_lists::get_shopping_list_by_id(existing_item.shopping_list_id, db).await?;
+        authorized(ShoppingListPolicy, DefaultActions::Update, user, list.as_ref(), db)?;
+
+        crate::shopping_lists::update_shopping_list_item(id, item, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn check_shopping_list_item(&self, ctx: &Context<'_>, id: i64, checked: bool) -> Result<ShoppingListItem> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let item = crate::shopping_lists::get_shopping_list_item_by_id(id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Shopping list item not found", Some(ctx.item.pos)))?;
+        let list = crate::shopping_lists::get_shopping_list_by_id(item.shopping_list_id, db).await?;
+        authorized(ShoppingListPolicy, DefaultActions::Update, user, list.as_ref(), db)?;
+
+        crate::shopping_lists::check_shopping_list_item(id, checked, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn delete_shopping_list_item(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let item = crate::shopping_lists::get_shopping_list_item_by_id(id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Shopping list item not found", Some(ctx.item.pos)))?;
+        let list = crate::shopping_lists::get_shopping_list_by_id(item.shopping_list_id, db).await?;
+        authorized(ShoppingListPolicy, DefaultActions::Update, user, list.as_ref(), db)?;
+
+        crate::shopping_lists::delete_shopping_list_item(item, db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
@@ -97,4 +97,31 @@ impl UsersMutations {
 
         crate::users::delete_user(id, db).await.map_err(|e| e.into())
     }
+
+    /// Creates a new secret URL for subscribing to the weekplan in a calendar app and returns it; previously created
+    /// URLs stop working
+    async fn create_calendar_feed(&self, ctx: &Context<'_>) -> Result<String> {
+        let current_user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(UsersPolicy, DefaultActions::Update, current_user, current_user, db)?;
+
+        // due to policy check user is always Some
+        let user = crate::users::create_calendar_token(current_user.unwrap().id, db).await?;
+
+        // the token has just been set
+        Ok(crate::calendar::feed_url(&user.calendar_token.unwrap()))
+    }
+
+    async fn revoke_calendar_feed(&self, ctx: &Context<'_>) -> Result<bool> {
+        let current_user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(UsersPolicy, DefaultActions::Update, current_user, current_user, db)?;
+
+        // due to policy check user is always Some
+        crate::users::revoke_calendar_token(current_user.unwrap().id, db).await?;
+
+        Ok(true)
+    }
 }
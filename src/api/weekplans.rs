@@ -1,10 +1,14 @@
 use async_graphql::*;
 use chrono::NaiveDate;
-use entity::weekplans::Model as Weekplan;
+use entity::weekplan_templates::Model as WeekplanTemplate;
+use entity::weekplans::{MealSlot, Model as Weekplan};
 use sea_orm::DatabaseConnection;
 
+use crate::authorization::recipes_policy::RecipesPolicy;
 use crate::authorization::weekplan_policy::WeekplanPolicy;
+use crate::authorization::weekplan_template_policy::WeekplanTemplatePolicy;
 use crate::authorization::{authorized, DefaultActions};
+use crate::weekplan_generator::WeekplanOptions;
 
 #[derive(Default)]
 pub struct WeekplansQueries;
@@ -14,7 +18,13 @@ pub struct WeekplansMutations;
 
 #[Object]
 impl WeekplansQueries {
-    async fn weekplans(&self, ctx: &Context<'_>, week: NaiveDate) -> Result<Vec<Weekplan>> {
+    /// The household's weekplan ordered by date and meal, only the entries of `mealSlot` when given
+    async fn weekplans(
+        &self,
+        ctx: &Context<'_>,
+        week: NaiveDate,
+        meal_slot: Option<MealSlot>,
+    ) -> Result<Vec<Weekplan>> {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
 
@@ -23,7 +33,21 @@ impl WeekplansQueries {
         // due to policy check user is always Some
         let user = user.unwrap();
 
-        crate::weekplan::list_weekplan(&week, user, db)
+        crate::weekplan::list_weekplan(&week, meal_slot, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn weekplan_templates(&self, ctx: &Context<'_>) -> Result<Vec<WeekplanTemplate>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(WeekplanTemplatePolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::weekplan::list_weekplan_templates(user, db)
             .await
             .map_err(|e| e.into())
     }
@@ -31,6 +55,7 @@ impl WeekplansQueries {
 
 #[Object]
 impl WeekplansMutations {
+    /// Fills the week of the current user's household, days already planned by any member are kept
     async fn create_weekplan(
         &self,
         ctx: &Context<'_>,
@@ -38,6 +63,7 @@ impl WeekplansMutations {
         tags: Vec<String>,
         portions: Option<i32>,
         days: Option<Vec<u32>>,
+        options: Option<WeekplanOptions>,
     ) -> Result<Vec<Weekplan>> {
         let user = ctx.data_opt::<entity::users::Model>();
         let db = ctx.data::<DatabaseConnection>()?;
@@ -47,9 +73,17 @@ impl WeekplansMutations {
         // due to policy check user is always Some
         let user = user.unwrap();
 
-        crate::weekplan::create_weekplan_for_week(week, user.to_owned(), tags, portions.unwrap_or(2), days, db)
-            .await
-            .map_err(|e| e.into())
+        crate::weekplan::create_weekplan_for_week(
+            week,
+            user.to_owned(),
+            tags,
+            portions.unwrap_or(2),
+            days,
+            options.unwrap_or_default(),
+            db,
+        )
+        .await
+        .map_err(|e| e.into())
     }
 
     async fn replace_weekplan_recipe(&self, ctx: &Context<'_>, id: i64, tags: Vec<String>) -> Result<Weekplan> {
@@ -82,7 +116,46 @@ impl WeekplansMutations {
         // due to policy check the entry is a Some
         let weekplan = weekplan.unwrap();
 
-        crate::weekplan::replace_weekplan_recipe_with_recipe(weekplan, recipe_id, db)
+        let recipe = crate::recipes::get_recipe_by_id(recipe_id, db)
+            .await?
+            .ok_or_else(|| ServerError::new("Recipe not found", Some(ctx.item.pos)))?;
+        authorized(RecipesPolicy, DefaultActions::Get, user, Some(&recipe), db)?;
+
+        crate::weekplan::replace_weekplan_recipe_with_recipe(weekplan, recipe.id, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Marks the entry as the leftovers of an earlier entry, its ingredients aren't bought or deducted from the pantry
+    /// then; without `leftoversOfId` the entry becomes an ordinary meal again
+    async fn set_weekplan_leftovers(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        leftovers_of_id: Option<i64>,
+    ) -> Result<Weekplan> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let weekplan = crate::weekplan::get_weekplan_by_id(id, db).await?;
+        authorized(WeekplanPolicy, DefaultActions::Update, user, weekplan.as_ref(), db)?;
+
+        // due to policy check the entry is a Some
+        let weekplan = weekplan.unwrap();
+
+        let cooked = match leftovers_of_id {
+            Some(leftovers_of_id) => {
+                let cooked = crate::weekplan::get_weekplan_by_id(leftovers_of_id, db)
+                    .await?
+                    .ok_or_else(|| ServerError::new("Weekplan not found", Some(ctx.item.pos)))?;
+                authorized(WeekplanPolicy, DefaultActions::Get, user, Some(&cooked), db)?;
+
+                Some(cooked)
+            }
+            None => None,
+        };
+
+        crate::weekplan::set_weekplan_leftovers(weekplan, cooked, db)
             .await
             .map_err(|e| e.into())
     }
@@ -94,6 +167,147 @@ impl WeekplansMutations {
         let weekplan = crate::weekplan::get_weekplan_by_id(id, db).await?;
         authorized(WeekplanPolicy, DefaultActions::Delete, user, weekplan.as_ref(), db)?;
 
-        crate::weekplan::delete_weekplan(id, db).await.map_err(|e| e.into())
+        // due to policy check the entry is a Some
+        crate::weekplan::delete_weekplan(weekplan.unwrap(), db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Saves the household's plan of the given week as a template
+    async fn save_weekplan_template(
+        &self,
+        ctx: &Context<'_>,
+        week: NaiveDate,
+        #[graphql(validator(chars_min_length = 1, chars_max_length = 255))] name: String,
+    ) -> Result<WeekplanTemplate> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        authorized(WeekplanTemplatePolicy, DefaultActions::Create, user, None, db)?;
+        authorized(WeekplanPolicy, DefaultActions::List, user, None, db)?;
+
+        // due to policy check user is always Some
+        let user = user.unwrap();
+
+        crate::weekplan::save_weekplan_template(week, name, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn delete_weekplan_template(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let template = crate::weekplan::get_weekplan_template_by_id(id, db).await?;
+        authorized(WeekplanTemplatePolicy, DefaultActions::Delete, user, template.as_ref(), db)?;
+
+        crate::weekplan::delete_weekplan_template(id, db)
+            .await
+            .map_err(|e| e.into())
     }
+
+    /// Plans the template's recipes in the given week, meals already planned are kept
+    async fn apply_weekplan_template(&self, ctx: &Context<'_>, id: i64, week: NaiveDate) -> Result<Vec<Weekplan>> {
+        let user = ctx.data_opt::<entity::users::Model>();
+        let db = ctx.data::<DatabaseConnection>()?;
+
+        let template = crate::weekplan::get_weekplan_template_by_id(id, db).await?;
+        authorized(WeekplanTemplatePolicy, DefaultActions::Get, user, template.as_ref(), db)?;
+        authorized(WeekplanPolicy, DefaultActions::Create, user, None, db)?;
+
+        // due to policy checks both are Some
+        let (user, template) = (user.unwrap(), template.unwrap());
+
+        crate::weekplan::apply_weekplan_template(&template, week, user, db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Copies the plan of one week to another week, meals already planned in the target week are kept
+    async fn copy_weekplan_week(
+        &self,
+        ctx: &Context<'_>,
+        from_week: NaiveDate,
+        to_week: NaiveDate,
+    ) -> Result<Vec<Weekplan>> {
+        copy_week(ctx, from_week, to_week, false).await
+    }
+
+    /// Moves the plan of one week to another week; entries for meals already planned in the target week stay where
+    /// they are
+    async fn move_weekplan_week(
+        &self,
+        ctx: &Context<'_>,
+        from_week: NaiveDate,
+        to_week: NaiveDate,
+    ) -> Result<Vec<Weekplan>> {
+        copy_week(ctx, from_week, to_week, true).await
+    }
+
+    async fn copy_weekplan_entry(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        date: NaiveDate,
+        meal_slot: Option<MealSlot>,
+    ) -> Result<Weekplan> {
+        copy_entry(ctx, id, date, meal_slot, false).await
+    }
+
+    async fn move_weekplan_entry(
+        &self,
+        ctx: &Context<'_>,
+        id: i64,
+        date: NaiveDate,
+        meal_slot: Option<MealSlot>,
+    ) -> Result<Weekplan> {
+        copy_entry(ctx, id, date, meal_slot, true).await
+    }
+}
+
+async fn copy_week(
+    ctx: &Context<'_>,
+    from_week: NaiveDate,
+    to_week: NaiveDate,
+    move_entries: bool,
+) -> Result<Vec<Weekplan>> {
+    let user = ctx.data_opt::<entity::users::Model>();
+    let db = ctx.data::<DatabaseConnection>()?;
+
+    authorized(WeekplanPolicy, DefaultActions::Create, user, None, db)?;
+
+    // due to policy check user is always Some
+    let user = user.unwrap();
+
+    crate::weekplan::copy_weekplan_week(from_week, to_week, move_entries, user, db)
+        .await
+        .map_err(|e| e.into())
+}
+
+async fn copy_entry(
+    ctx: &Context<'_>,
+    id: i64,
+    date: NaiveDate,
+    meal_slot: Option<MealSlot>,
+    move_entry: bool,
+) -> Result<Weekplan> {
+    let user = ctx.data_opt::<entity::users::Model>();
+    let db = ctx.data::<DatabaseConnection>()?;
+
+    let action = if move_entry {
+        DefaultActions::Update
+    } else {
+        DefaultActions::Get
+    };
+
+    let weekplan = crate::weekplan::get_weekplan_by_id(id, db).await?;
+    authorized(WeekplanPolicy, action, user, weekplan.as_ref(), db)?;
+    authorized(WeekplanPolicy, DefaultActions::Create, user, None, db)?;
+
+    // due to policy checks both are Some
+    let (user, weekplan) = (user.unwrap(), weekplan.unwrap());
+
+    crate::weekplan::copy_weekplan_entry(weekplan, date, meal_slot, move_entry, user, db)
+        .await
+        .map_err(|e| e.into())
 }
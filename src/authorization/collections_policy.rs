@@ -0,0 +1,55 @@
+use entity::collection_collaborators::CollaboratorRole;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, is_root};
+use crate::collections::CollectionWithRole;
+
+pub enum CollectionActions {
+    List,
+    Create,
+    Get,
+    /// change name, description and cover image and add, remove or reorder recipes
+    Update,
+    Delete,
+    /// add, change or remove collaborators
+    Share,
+}
+
+pub struct CollectionsPolicy;
+
+impl Authorization<CollectionActions, CollectionWithRole> for CollectionsPolicy {
+    fn authorized(
+        &self,
+        action: CollectionActions,
+        user: Option<&UserModel>,
+        resource: Option<&CollectionWithRole>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            CollectionActions::List | CollectionActions::Create => user.is_some(),
+            CollectionActions::Get
+            | CollectionActions::Update
+            | CollectionActions::Delete
+            | CollectionActions::Share => {
+                if is_root(user) {
+                    return true;
+                }
+
+                let (Some(user), Some(collection)) = (user, resource) else {
+                    return false;
+                };
+
+                if collection.collection.owner_id == user.id {
+                    return true;
+                }
+
+                match action {
+                    CollectionActions::Get => collection.role.is_some(),
+                    CollectionActions::Update => collection.role == Some(CollaboratorRole::Editor),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
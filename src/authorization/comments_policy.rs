@@ -0,0 +1,35 @@
+use entity::recipe_comments::Model as CommentModel;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions, is_root};
+
+pub struct CommentsPolicy;
+
+impl Authorization<DefaultActions, CommentModel> for CommentsPolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&CommentModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List => true,
+            DefaultActions::Create => user.is_some(),
+            DefaultActions::Get => true,
+            DefaultActions::Update | DefaultActions::Delete => {
+                // root moderates all comments
+                if is_root(user) {
+                    return true;
+                }
+
+                if let (Some(user), Some(comment)) = (user, resource) {
+                    return comment.author_id == user.id;
+                }
+
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use entity::cook_log::Model as CookLogModel;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions, is_root};
+
+pub struct CookLogPolicy;
+
+impl Authorization<DefaultActions, CookLogModel> for CookLogPolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&CookLogModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List => user.is_some(),
+            DefaultActions::Create => user.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if is_root(user) {
+                    return true;
+                }
+
+                if let (Some(user), Some(entry)) = (user, resource) {
+                    return entry.user_id == user.id;
+                }
+
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use entity::favorites::Model as FavoriteModel;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions};
+
+pub struct FavoritesPolicy;
+
+impl Authorization<DefaultActions, FavoriteModel> for FavoritesPolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&FavoriteModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List | DefaultActions::Create => user.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if let (Some(user), Some(favorite)) = (user, resource) {
+                    return favorite.user_id == user.id;
+                }
+
+                false
+            }
+        }
+    }
+}
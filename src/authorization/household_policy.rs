@@ -0,0 +1,33 @@
+use entity::households::Model as HouseholdModel;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions, is_root};
+
+pub struct HouseholdPolicy;
+
+impl Authorization<DefaultActions, HouseholdModel> for HouseholdPolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&HouseholdModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List => user.is_some(),
+            DefaultActions::Create => user.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if is_root(user) {
+                    return true;
+                }
+
+                if let (Some(user), Some(household)) = (user, resource) {
+                    return household.id == user.household_id;
+                }
+
+                false
+            }
+        }
+    }
+}
@@ -2,10 +2,19 @@ use async_graphql::Error;
 use entity::users::{Model as UserModel, Role};
 use sea_orm::DatabaseConnection;
 
+pub mod collections_policy;
+pub mod comments_policy;
+pub mod cook_log_policy;
+pub mod favorites_policy;
+pub mod household_policy;
 pub mod ingredients_policy;
+pub mod nutrition_targets_policy;
+pub mod pantry_items_policy;
 pub mod recipes_policy;
+pub mod shopping_list_policy;
 pub mod users_policy;
 pub mod weekplan_policy;
+pub mod weekplan_template_policy;
 
 pub enum DefaultActions {
     List,
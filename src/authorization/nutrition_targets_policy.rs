@@ -0,0 +1,33 @@
+use entity::nutrition_targets::Model as NutritionTargetsModel;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions, is_root};
+
+pub struct NutritionTargetsPolicy;
+
+impl Authorization<DefaultActions, NutritionTargetsModel> for NutritionTargetsPolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&NutritionTargetsModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List => user.is_some(),
+            DefaultActions::Create => user.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if is_root(user) {
+                    return true;
+                }
+
+                if let (Some(user), Some(targets)) = (user, resource) {
+                    return targets.user_id == user.id;
+                }
+
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use entity::pantry_items::Model as PantryItemModel;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions, is_root};
+
+pub struct PantryItemsPolicy;
+
+impl Authorization<DefaultActions, PantryItemModel> for PantryItemsPolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&PantryItemModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List => user.is_some(),
+            DefaultActions::Create => user.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if is_root(user) {
+                    return true;
+                }
+
+                if let (Some(user), Some(item)) = (user, resource) {
+                    return item.user_id == user.id;
+                }
+
+                false
+            }
+        }
+    }
+}
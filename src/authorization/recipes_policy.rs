@@ -17,7 +17,7 @@ impl Authorization<DefaultActions, RecipeModel> for RecipesPolicy {
         match action {
             DefaultActions::List => true,
             DefaultActions::Create => user.is_some(),
-            DefaultActions::Get => true,
+            DefaultActions::Get => resource.is_none_or(|recipe| recipe.visible_to(user)),
             DefaultActions::Update => {
                 if let Some(user) = user {
                     if user.role == Role::Root {
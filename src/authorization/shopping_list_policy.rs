@@ -0,0 +1,33 @@
+use entity::shopping_lists::Model as ShoppingListModel;
+use entity::users::Model as UserModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions, is_root};
+
+pub struct ShoppingListPolicy;
+
+impl Authorization<DefaultActions, ShoppingListModel> for ShoppingListPolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&ShoppingListModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List => user.is_some(),
+            DefaultActions::Create => user.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if is_root(user) {
+                    return true;
+                }
+
+                if let (Some(user), Some(list)) = (user, resource) {
+                    return list.household_id == user.household_id;
+                }
+
+                false
+            }
+        }
+    }
+}
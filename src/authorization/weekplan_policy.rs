@@ -2,7 +2,7 @@ use entity::users::Model as UserModel;
 use entity::weekplans::Model as WeekplanModel;
 use sea_orm::DatabaseConnection;
 
-use super::{Authorization, DefaultActions};
+use super::{Authorization, DefaultActions, is_root};
 
 pub struct WeekplanPolicy;
 
@@ -17,9 +17,18 @@ impl Authorization<DefaultActions, WeekplanModel> for WeekplanPolicy {
         match action {
             DefaultActions::List => user.is_some(),
             DefaultActions::Create => user.is_some(),
-            DefaultActions::Get => user.is_some() && resource.is_some(),
-            DefaultActions::Update => user.is_some() && resource.is_some(),
-            DefaultActions::Delete => user.is_some() && resource.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if is_root(user) {
+                    return true;
+                }
+
+                // all members of a household share its weekplan
+                if let (Some(user), Some(weekplan)) = (user, resource) {
+                    return weekplan.household_id == user.household_id;
+                }
+
+                false
+            }
         }
     }
 }
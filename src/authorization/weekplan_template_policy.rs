@@ -0,0 +1,33 @@
+use entity::users::Model as UserModel;
+use entity::weekplan_templates::Model as WeekplanTemplateModel;
+use sea_orm::DatabaseConnection;
+
+use super::{Authorization, DefaultActions, is_root};
+
+pub struct WeekplanTemplatePolicy;
+
+impl Authorization<DefaultActions, WeekplanTemplateModel> for WeekplanTemplatePolicy {
+    fn authorized(
+        &self,
+        action: DefaultActions,
+        user: Option<&UserModel>,
+        resource: Option<&WeekplanTemplateModel>,
+        _db: &DatabaseConnection,
+    ) -> bool {
+        match action {
+            DefaultActions::List => user.is_some(),
+            DefaultActions::Create => user.is_some(),
+            DefaultActions::Get | DefaultActions::Update | DefaultActions::Delete => {
+                if is_root(user) {
+                    return true;
+                }
+
+                if let (Some(user), Some(template)) = (user, resource) {
+                    return template.user_id == user.id;
+                }
+
+                false
+            }
+        }
+    }
+}
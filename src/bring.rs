@@ -1,15 +1,18 @@
 use std::collections::HashMap;
+use std::hash::Hash;
 
 use axum::extract::{Path, Query, State};
 use axum::routing::get;
 use axum::{Json, Router, debug_handler};
 use chrono::{Datelike, NaiveDate};
-use entity::{ingredient_units, ingredients, steps, steps_ingredients};
-use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
+use entity::ingredient_units::Language;
+use entity::weekplans::MealSlot;
+use entity::{ingredient_units, ingredients, steps, steps_ingredients, unit_conversion};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryFilter};
 use serde::Deserialize;
 
 use crate::types::HttpError;
-use crate::{AppState, recipes, users, weekplan};
+use crate::{AppState, pantry, recipes, users, weekplan};
 
 pub(crate) fn routes() -> Router<AppState> {
     Router::new()
@@ -36,11 +39,41 @@ pub struct PortionsQuery {
     pub portions: Option<f64>,
 }
 
-struct BringInfo {
-    ingredient: ingredients::Model,
-    unit: Option<ingredient_units::Model>,
-    amount: f64,
-    notes: Vec<String>,
+pub(crate) struct BringInfo {
+    pub ingredient: ingredients::Model,
+    pub unit: Option<ingredient_units::Model>,
+    pub amount: f64,
+    pub notes: Vec<String>,
+}
+
+impl BringInfo {
+    fn new(ingredient: ingredients::Model) -> Self {
+        Self {
+            ingredient,
+            unit: None,
+            amount: 0.0,
+            notes: Vec::new(),
+        }
+    }
+
+    /// Adds an amount to the sum. Amounts in pieces are counted as pieces as long as every amount of the ingredient
+    /// is given in pieces, everything else is converted to the ingredient's reference (g/ml).
+    fn add(&mut self, amount: f64, unit: Option<&ingredient_units::Model>) {
+        let is_pcs = unit.is_some_and(|u| u.identifier == ingredient_units::Units::PCS);
+        let counts_pcs = self.unit.is_some();
+
+        if is_pcs && (counts_pcs || self.amount == 0.0) {
+            self.unit = unit.cloned();
+            self.amount += amount;
+            return;
+        }
+
+        if let Some(pcs) = self.unit.take() {
+            self.amount = unit_conversion::to_reference(self.amount, Some(pcs.base_value));
+        }
+
+        self.amount += unit_conversion::to_reference(amount, unit.map(|u| u.base_value));
+    }
 }
 
 #[debug_handler]
@@ -51,8 +84,10 @@ pub async fn get_recipe_bring(
 ) -> Result<Json<BringRecipe>, HttpError> {
     let db = &state.conn;
 
+    // the route is public, private recipes are treated like missing ones
     let recipe = recipes::get_recipe_by_id(*id, db)
         .await?
+        .filter(|recipe| recipe.visible_to(None))
         .ok_or_else(|| HttpError::not_found(Some("recipe not found")))?;
 
     let portions = match params.portions {
@@ -101,33 +136,18 @@ pub async fn get_recipe_bring(
         .all(db)
         .await?;
 
-    let mut all_ingredients: HashMap<i64, HashMap<i64, BringInfo>> = HashMap::new();
+    let mut all_ingredients: HashMap<i64, BringInfo> = HashMap::new();
 
     for si in step_ingredients {
-        let mut unit_key = si.unit_id.unwrap_or(-1);
-        let mut unit = units.iter().find(|u| u.id == unit_key).cloned();
-        let mut factor = unit.as_ref().map(|u| u.base_value).unwrap_or(1.0);
-        let ingredient = ingredients.iter().find(|i| i.id == si.ingredient_id).unwrap().clone();
-
-        if let Some(iunit) = &unit {
-            if iunit.identifier == ingredient_units::Units::PCS {
-                factor = 1.0;
-            } else {
-                unit_key = -1;
-                unit = None;
-            }
-        }
+        let unit = units.iter().find(|u| Some(u.id) == si.unit_id);
+        let ingredient = ingredients.iter().find(|i| i.id == si.ingredient_id).unwrap();
 
-        let row = all_ingredients.entry(si.ingredient_id).or_default();
-        let info = row.entry(unit_key).or_insert(BringInfo {
-            ingredient,
-            unit,
-            amount: 0.0,
-            notes: Vec::new(),
-        });
+        let info = all_ingredients
+            .entry(si.ingredient_id)
+            .or_insert_with(|| BringInfo::new(ingredient.clone()));
 
         if let Some(amount) = si.amount {
-            info.amount += amount * factor;
+            info.add(amount, unit);
         }
 
         if let Some(note) = si.annotation {
@@ -140,7 +160,6 @@ pub async fn get_recipe_bring(
         author: owner.name.unwrap_or(owner.email),
         items: all_ingredients
             .values()
-            .flat_map(|row| row.values())
             .map(|info| BringItem {
                 item_id: info.ingredient.name.clone(),
                 spec: calc_amount(info.amount, portions, &info.unit),
@@ -158,7 +177,7 @@ fn calc_amount(amount: f64, portions: f64, unit: &Option<ingredient_units::Model
         if let Some(unit) = &unit {
             let grams = amount * portions * unit.base_value;
 
-            return format!("{:.2} {} ({:.0}g)", amount, unit_to_str(&unit.identifier), grams);
+            return format!("{:.2} {} ({:.0}g)", amount, unit.identifier.display_name(Language::De), grams);
         }
 
         format!("{:.0}g", amount)
@@ -171,6 +190,10 @@ fn calc_amount(amount: f64, portions: f64, unit: &Option<ingredient_units::Model
 pub struct WeekplanQuery {
     pub week: NaiveDate,
     pub days: Option<Vec<u32>>,
+    /// only the ingredients of this meal, e.g. `breakfast`
+    pub meal_slot: Option<MealSlot>,
+    /// leave out what is already in the user's pantry
+    pub subtract_pantry: Option<bool>,
 }
 
 #[debug_handler]
@@ -185,7 +208,7 @@ pub async fn get_weekplan_bring(
         .await
         .ok_or_else(|| HttpError::not_found(Some("User not found")))?;
 
-    let mut weekplans = weekplan::list_weekplan(&params.week, &user, db).await?;
+    let mut weekplans = weekplan::list_weekplan(&params.week, params.meal_slot, &user, db).await?;
 
     if let Some(days) = &params.days {
         weekplans = weekplans
@@ -194,6 +217,58 @@ pub async fn get_weekplan_bring(
             .collect::<Vec<entity::weekplans::Model>>();
     }
 
+    let mut ingredients = aggregate_weekplan_ingredients(&weekplans, db).await?;
+
+    if params.subtract_pantry.unwrap_or(false) {
+        ingredients = pantry::subtract_stock(ingredients, &user, db).await?;
+    }
+
+    let desc = BringRecipe {
+        name: "Weekplan".to_owned(),
+        author: user.name.unwrap_or(user.email),
+        items: ingredients
+            .iter()
+            .map(|info| BringItem {
+                item_id: info.ingredient.name.clone(),
+                spec: amount_str(info.amount, &info.unit),
+            })
+            .collect(),
+    };
+
+    Ok(Json(desc))
+}
+
+/// Sums up the ingredients of all recipes in the given weekplan entries, scaled by the entry's portions. There is one
+/// entry per ingredient, see [`BringInfo::add`] for how differing units are summed up. Leftovers are skipped, they
+/// have been cooked with the entry they are the leftovers of.
+pub(crate) async fn aggregate_weekplan_ingredients(
+    weekplans: &[entity::weekplans::Model],
+    db: &DatabaseConnection,
+) -> Result<Vec<BringInfo>, DbErr> {
+    let mut groups = aggregate_grouped(weekplans, |_| (), db).await?;
+    Ok(groups.remove(&()).unwrap_or_default())
+}
+
+/// Like [`aggregate_weekplan_ingredients`], but the ingredients are summed up per weekplan entry, keyed by the entry's
+/// id. Entries without ingredients, like leftovers, are missing.
+pub(crate) async fn aggregate_ingredients_per_entry(
+    weekplans: &[entity::weekplans::Model],
+    db: &DatabaseConnection,
+) -> Result<HashMap<i64, Vec<BringInfo>>, DbErr> {
+    aggregate_grouped(weekplans, |entry| entry.id, db).await
+}
+
+/// Loads the step ingredients of all entries at once and sums them up per group of entries
+async fn aggregate_grouped<K: Eq + Hash>(
+    weekplans: &[entity::weekplans::Model],
+    group: impl Fn(&entity::weekplans::Model) -> K,
+    db: &DatabaseConnection,
+) -> Result<HashMap<K, Vec<BringInfo>>, DbErr> {
+    let weekplans = weekplans
+        .iter()
+        .filter(|entry| entry.leftovers_of_id.is_none())
+        .collect::<Vec<_>>();
+
     let recipe_ids = weekplans.iter().map(|r| r.recipe_id).collect::<Vec<i64>>();
     let step_ingredients = entity::steps::Entity::find()
         .filter(entity::steps::Column::RecipeId.is_in(recipe_ids))
@@ -225,7 +300,7 @@ pub async fn get_weekplan_bring(
         .all(db)
         .await?;
 
-    let mut all_ingredients: HashMap<i64, HashMap<i64, BringInfo>> = HashMap::new();
+    let mut groups: HashMap<K, HashMap<i64, BringInfo>> = HashMap::new();
 
     for weekplan_entry in weekplans {
         let step_ingredients = step_ingredients
@@ -235,30 +310,17 @@ pub async fn get_weekplan_bring(
             .collect::<Vec<&steps_ingredients::Model>>();
 
         for si in step_ingredients {
-            let mut unit_key = si.unit_id.unwrap_or(-1);
-            let mut unit = units.iter().find(|u| u.id == unit_key).cloned();
-            let mut factor = unit.as_ref().map(|u| u.base_value).unwrap_or(1.0);
-            let ingredient = ingredients.iter().find(|i| i.id == si.ingredient_id).unwrap().clone();
-
-            if let Some(iunit) = &unit {
-                if iunit.identifier == ingredient_units::Units::PCS {
-                    factor = 1.0;
-                } else {
-                    unit_key = -1;
-                    unit = None;
-                }
-            }
+            let unit = units.iter().find(|u| Some(u.id) == si.unit_id);
+            let ingredient = ingredients.iter().find(|i| i.id == si.ingredient_id).unwrap();
 
-            let row = all_ingredients.entry(si.ingredient_id).or_default();
-            let info = row.entry(unit_key).or_insert(BringInfo {
-                ingredient,
-                unit,
-                amount: 0.0,
-                notes: Vec::new(),
-            });
+            let info = groups
+                .entry(group(weekplan_entry))
+                .or_default()
+                .entry(si.ingredient_id)
+                .or_insert_with(|| BringInfo::new(ingredient.clone()));
 
             if let Some(amount) = si.amount {
-                info.amount += amount * factor * (weekplan_entry.portions as f64);
+                info.add(amount * (weekplan_entry.portions as f64), unit);
             }
 
             if let Some(note) = &si.annotation {
@@ -267,27 +329,17 @@ pub async fn get_weekplan_bring(
         }
     }
 
-    let desc = BringRecipe {
-        name: "Weekplan".to_owned(),
-        author: user.name.unwrap_or(user.email),
-        items: all_ingredients
-            .values()
-            .flat_map(|row| row.values())
-            .map(|info| BringItem {
-                item_id: info.ingredient.name.clone(),
-                spec: amount_str(info.amount, &info.unit),
-            })
-            .collect(),
-    };
-
-    Ok(Json(desc))
+    Ok(groups
+        .into_iter()
+        .map(|(key, ingredients)| (key, ingredients.into_values().collect()))
+        .collect())
 }
 
-fn amount_str(amount: f64, unit: &Option<ingredient_units::Model>) -> String {
+pub(crate) fn amount_str(amount: f64, unit: &Option<ingredient_units::Model>) -> String {
     if amount > 0.0 {
         if let Some(unit) = &unit {
             let grams = amount * unit.base_value;
-            return format!("{:.2} {} ({:.0}g)", amount, unit_to_str(&unit.identifier), grams);
+            return format!("{:.2} {} ({:.0}g)", amount, unit.identifier.display_name(Language::De), grams);
         }
 
         format!("{:.0}g", amount)
@@ -295,13 +347,3 @@ fn amount_str(amount: f64, unit: &Option<ingredient_units::Model>) -> String {
         "".to_owned()
     }
 }
-
-fn unit_to_str(unit: &ingredient_units::Units) -> &str {
-    match unit {
-        ingredient_units::Units::PCS => "Stück",
-        ingredient_units::Units::TBSP => "Esslöffel",
-        ingredient_units::Units::TSP => "Teelöffel",
-        ingredient_units::Units::SKOSH => "Prise",
-        ingredient_units::Units::PINCH => "Messerspitze",
-    }
-}
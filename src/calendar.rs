@@ -0,0 +1,180 @@
+//! The household's weekplan as an [iCalendar](https://datatracker.ietf.org/doc/html/rfc5545) feed, so it can be
+//! subscribed to in calendar apps. The feed is protected by a secret token instead of the session, calendar apps
+//! can't log in.
+
+use std::collections::HashMap;
+
+use axum::extract::{Path, Query, State};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Router, debug_handler};
+use chrono::{Duration, NaiveDateTime, NaiveTime, Utc};
+use entity::weekplans::MealSlot;
+use http::header;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+
+use crate::bring::{BringInfo, aggregate_ingredients_per_entry, amount_str};
+use crate::types::HttpError;
+use crate::utils::public_url;
+use crate::{AppState, users, weekplan};
+
+/// How many weeks of the past are part of the feed
+const WEEKS_BEFORE: i64 = 4;
+/// How many weeks of the future are part of the feed
+const WEEKS_AFTER: i64 = 8;
+
+pub(crate) fn routes() -> Router<AppState> {
+    Router::new().route("/calendar/{token}/weekplan.ics", get(get_weekplan_calendar))
+}
+
+pub(crate) fn feed_url(token: &str) -> String {
+    format!("{}/calendar/{}/weekplan.ics", public_url(), token)
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CalendarQuery {
+    /// when dinner is served, e.g. `19:00`; defaults to 18:30
+    pub dinner_time: Option<NaiveTime>,
+    /// how long a meal event lasts in minutes; defaults to 60
+    pub duration: Option<u32>,
+}
+
+impl CalendarQuery {
+    fn start_time(&self, meal_slot: MealSlot) -> NaiveTime {
+        match meal_slot {
+            MealSlot::Breakfast => NaiveTime::from_hms_opt(7, 30, 0).unwrap(),
+            MealSlot::Lunch => NaiveTime::from_hms_opt(12, 30, 0).unwrap(),
+            MealSlot::Snack => NaiveTime::from_hms_opt(15, 30, 0).unwrap(),
+            MealSlot::Dinner => self
+                .dinner_time
+                .unwrap_or_else(|| NaiveTime::from_hms_opt(18, 30, 0).unwrap()),
+        }
+    }
+
+    fn duration(&self) -> Duration {
+        Duration::minutes(self.duration.unwrap_or(60).clamp(1, 24 * 60) as i64)
+    }
+}
+
+#[debug_handler]
+pub async fn get_weekplan_calendar(
+    Path(token): Path<String>,
+    Query(params): Query<CalendarQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, HttpError> {
+    let db = &state.conn;
+
+    let user = users::get_user_by_calendar_token(&token, db)
+        .await?
+        .ok_or_else(|| HttpError::not_found(Some("calendar not found")))?;
+
+    let today = Utc::now().date_naive();
+    let entries = weekplan::list_weekplan_between(
+        today - Duration::weeks(WEEKS_BEFORE),
+        today + Duration::weeks(WEEKS_AFTER),
+        &user,
+        db,
+    )
+    .await?;
+
+    let recipes = entity::recipes::Entity::find()
+        .filter(entity::recipes::Column::Id.is_in(entries.iter().map(|entry| entry.recipe_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|recipe| (recipe.id, recipe))
+        .collect::<HashMap<i64, entity::recipes::Model>>();
+
+    let mut ingredients = aggregate_ingredients_per_entry(&entries, db).await?;
+
+    let mut calendar = Calendar::default();
+    calendar.line("BEGIN:VCALENDAR");
+    calendar.line("VERSION:2.0");
+    calendar.line("PRODID:-//recipes//weekplan//DE");
+    calendar.line("CALSCALE:GREGORIAN");
+    calendar.line("METHOD:PUBLISH");
+    calendar.property("X-WR-CALNAME", "Weekplan");
+
+    for entry in &entries {
+        let Some(recipe) = recipes.get(&entry.recipe_id) else {
+            continue;
+        };
+
+        let start = entry.date.and_time(params.start_time(entry.meal_slot));
+        let url = format!("{}/recipes/{}", public_url(), recipe.id);
+        let ingredients = ingredients.remove(&entry.id).unwrap_or_default();
+        let description = format!("{}\n\n{}", url, ingredient_list(ingredients));
+        let summary = match entry.leftovers_of_id {
+            Some(_) => format!("{} (leftovers)", recipe.name),
+            None => recipe.name.clone(),
+        };
+
+        calendar.line("BEGIN:VEVENT");
+        calendar.line(&format!("UID:weekplan-{}@recipes", entry.id));
+        calendar.line(&format!("DTSTAMP:{}", entry.updated_at.format("%Y%m%dT%H%M%SZ")));
+        calendar.line(&format!("DTSTART:{}", ical_datetime(start)));
+        calendar.line(&format!("DTEND:{}", ical_datetime(start + params.duration())));
+        calendar.property("SUMMARY", &summary);
+        calendar.property("DESCRIPTION", &description);
+        calendar.line(&format!("URL:{}", url));
+        calendar.line("END:VEVENT");
+    }
+
+    calendar.line("END:VCALENDAR");
+
+    Ok(([(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], calendar.0))
+}
+
+/// The ingredients of an entry's recipe scaled to the planned portions, one per line
+fn ingredient_list(mut ingredients: Vec<BringInfo>) -> String {
+    ingredients.sort_by(|a, b| a.ingredient.name.cmp(&b.ingredient.name));
+
+    ingredients
+        .iter()
+        .map(|info| match amount_str(info.amount, &info.unit) {
+            amount if amount.is_empty() => format!("- {}", info.ingredient.name),
+            amount => format!("- {} {}", amount, info.ingredient.name),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Date and time without a time zone, calendar apps show them in the local time of the device
+fn ical_datetime(date: NaiveDateTime) -> String {
+    date.format("%Y%m%dT%H%M%S").to_string()
+}
+
+#[derive(Default)]
+struct Calendar(String);
+
+impl Calendar {
+    /// Appends a content line, folded to lines of at most 75 octets as required by the RFC
+    fn line(&mut self, line: &str) {
+        let mut len = 0;
+
+        for c in line.chars() {
+            if len + c.len_utf8() > 75 {
+                self.0.push_str("\r\n ");
+                len = 1;
+            }
+
+            self.0.push(c);
+            len += c.len_utf8();
+        }
+
+        self.0.push_str("\r\n");
+    }
+
+    /// Appends a property with a text value, escaping the characters with a special meaning
+    fn property(&mut self, name: &str, value: &str) {
+        let value = value
+            .replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\r', "")
+            .replace('\n', "\\n");
+
+        self.line(&format!("{}:{}", name, value));
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+use std::fs::{File, remove_dir_all};
+use std::io::copy;
+
+use async_graphql::*;
+use entity::collection_collaborators::{self as CollectionCollaborator, CollaboratorRole};
+use entity::users::Model as User;
+use entity::{collections as Collection, collections_recipes as CollectionRecipe};
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{Condition, DatabaseConnection, DbErr, QueryOrder, QuerySelect, TransactionTrait};
+
+use crate::utils::{get_extension_from_filename, image_base_path};
+
+#[derive(InputObject)]
+pub struct CollectionInput {
+    #[graphql(validator(chars_min_length = 1, chars_max_length = 255))]
+    pub name: String,
+    pub description: Option<String>,
+    /// the cover image, the current one is kept when empty
+    pub image: Option<Upload>,
+}
+
+/// A collection together with the role the current user has in it, `None` when the user is the owner or not a
+/// collaborator
+pub struct CollectionWithRole {
+    pub collection: Collection::Model,
+    pub role: Option<CollaboratorRole>,
+}
+
+pub async fn get_collection_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<Collection::Model>, DbErr> {
+    Collection::Entity::find_by_id(id).one(db).await
+}
+
+pub async fn get_collection_with_role(
+    id: i64,
+    user: Option<&User>,
+    db: &DatabaseConnection,
+) -> Result<Option<CollectionWithRole>, DbErr> {
+    let Some(collection) = get_collection_by_id(id, db).await? else {
+        return Ok(None);
+    };
+
+    let role = match user {
+        Some(user) => CollectionCollaborator::Entity::find_by_id((collection.id, user.id))
+            .one(db)
+            .await?
+            .map(|collaborator| collaborator.role),
+        None => None,
+    };
+
+    Ok(Some(CollectionWithRole { collection, role }))
+}
+
+/// The collections the user owns or collaborates on, ordered by name
+pub async fn list_collections(
+    limit: u64,
+    offset: u64,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<Collection::Model>, DbErr> {
+    Collection::Entity::find()
+        .filter(
+            Condition::any().add(Collection::Column::OwnerId.eq(user.id)).add(
+                Expr::col((Collection::Entity, Collection::Column::Id)).in_subquery(
+                    Query::select()
+                        .column(CollectionCollaborator::Column::CollectionId)
+                        .from(CollectionCollaborator::Entity)
+                        .and_where(Expr::col(CollectionCollaborator::Column::UserId).eq(user.id))
+                        .to_owned(),
+                ),
+            ),
+        )
+        .order_by_asc(Collection::Column::Name)
+        .order_by_asc(Collection::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await
+}
+
+pub async fn create_collection(
+    values: CollectionInput,
+    picture: Option<UploadValue>,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Collection::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let mut collection = Collection::ActiveModel {
+        owner_id: Set(user.id),
+        name: Set(values.name),
+        description: Set(values.description),
+        inserted_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    if let Some(ref picture) = picture {
+        collection.image = Set(Some(picture.filename.clone()));
+    }
+
+    db.transaction::<_, Collection::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let collection = collection.insert(txn).await?;
+
+            if let Some(picture) = picture {
+                save_cover(&collection, picture)?;
+            }
+
+            Ok(collection)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+pub async fn update_collection(
+    id: i64,
+    values: CollectionInput,
+    picture: Option<UploadValue>,
+    db: &DatabaseConnection,
+) -> Result<Collection::Model, DbErr> {
+    let mut collection = Collection::ActiveModel {
+        id: Unchanged(id),
+        name: Set(values.name),
+        description: Set(values.description),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    if let Some(ref picture) = picture {
+        collection.image = Set(Some(picture.filename.clone()));
+    }
+
+    db.transaction::<_, Collection::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let collection = collection.update(txn).await?;
+
+            if let Some(picture) = picture {
+                save_cover(&collection, picture)?;
+            }
+
+            Ok(collection)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+pub async fn delete_collection(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    let deleted = Collection::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1;
+
+    if deleted {
+        let _ = remove_dir_all(cover_path(id));
+    }
+
+    Ok(deleted)
+}
+
+fn cover_path(id: i64) -> String {
+    format!("{}/collections/{}", image_base_path(), id)
+}
+
+fn save_cover(collection: &Collection::Model, mut picture: UploadValue) -> Result<(), DbErr> {
+    let dir = cover_path(collection.id);
+
+    std::fs::create_dir_all(&dir).map_err(|e| DbErr::Custom(format!("Failed to create picture directory: {}", e)))?;
+
+    let ext = get_extension_from_filename(&picture.filename)
+        .unwrap_or("jpg")
+        .to_owned();
+    let path = format!("{}/original.{}", dir, ext);
+    let mut file = File::create(path).map_err(|e| DbErr::Custom(format!("Failed to create picture: {}", e)))?;
+    copy(&mut picture.content, &mut file).map_err(|e| DbErr::Custom(format!("Failed to copy picture: {}", e)))?;
+
+    tokio::task::spawn_blocking(move || crate::recipes::generate_picture_sizes(&dir, &ext));
+
+    Ok(())
+}
+
+/// Appends the recipe to the collection; adding a recipe already in the collection is a no-op
+pub async fn add_recipe(
+    collection_id: i64,
+    recipe_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Collection::Model, DbErr> {
+    let existing = CollectionRecipe::Entity::find_by_id((collection_id, recipe_id))
+        .one(db)
+        .await?;
+
+    if existing.is_none() {
+        let last_position: Option<i32> = CollectionRecipe::Entity::find()
+            .select_only()
+            .column_as(CollectionRecipe::Column::Position.max(), "position")
+            .filter(CollectionRecipe::Column::CollectionId.eq(collection_id))
+            .into_tuple()
+            .one(db)
+            .await?
+            .flatten();
+
+        CollectionRecipe::ActiveModel {
+            collection_id: Set(collection_id),
+            recipe_id: Set(recipe_id),
+            position: Set(last_position.map_or(0, |position| position + 1)),
+        }
+        .insert(db)
+        .await?;
+    }
+
+    touch_collection(collection_id, db).await
+}
+
+pub async fn remove_recipe(
+    collection_id: i64,
+    recipe_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Collection::Model, DbErr> {
+    CollectionRecipe::Entity::delete_by_id((collection_id, recipe_id))
+        .exec(db)
+        .await?;
+
+    touch_collection(collection_id, db).await
+}
+
+/// Sets the order of the recipes; `recipe_ids` has to contain every recipe of the collection exactly once
+pub async fn reorder_recipes(
+    collection_id: i64,
+    recipe_ids: Vec<i64>,
+    db: &DatabaseConnection,
+) -> Result<Collection::Model, DbErr> {
+    let current = CollectionRecipe::Entity::find()
+        .filter(CollectionRecipe::Column::CollectionId.eq(collection_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|entry| entry.recipe_id)
+        .collect::<HashSet<_>>();
+
+    let given = recipe_ids.iter().copied().collect::<HashSet<_>>();
+
+    if given.len() != recipe_ids.len() || given != current {
+        return Err(DbErr::Custom(
+            "The recipe list has to contain every recipe of the collection exactly once".to_owned(),
+        ));
+    }
+
+    db.transaction::<_, (), DbErr>(|txn| {
+        Box::pin(async move {
+            for (position, recipe_id) in recipe_ids.into_iter().enumerate() {
+                CollectionRecipe::ActiveModel {
+                    collection_id: Unchanged(collection_id),
+                    recipe_id: Unchanged(recipe_id),
+                    position: Set(position as i32),
+                }
+                .update(txn)
+                .await?;
+            }
+
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))?;
+
+    touch_collection(collection_id, db).await
+}
+
+async fn touch_collection(id: i64, db: &DatabaseConnection) -> Result<Collection::Model, DbErr> {
+    Collection::ActiveModel {
+        id: Unchanged(id),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+/// Shares the collection with the user with the given email or changes their role
+pub async fn set_collaborator(
+    collection: &Collection::Model,
+    email: String,
+    role: CollaboratorRole,
+    db: &DatabaseConnection,
+) -> Result<CollectionCollaborator::Model, DbErr> {
+    let Some(user) = crate::users::get_user_by_email(email, db).await else {
+        return Err(DbErr::RecordNotFound("User not found".to_owned()));
+    };
+
+    if user.id == collection.owner_id {
+        return Err(DbErr::Custom("The owner can't be a collaborator".to_owned()));
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let existing = CollectionCollaborator::Entity::find_by_id((collection.id, user.id))
+        .one(db)
+        .await?;
+
+    match existing {
+        Some(_) => {
+            CollectionCollaborator::ActiveModel {
+                collection_id: Unchanged(collection.id),
+                user_id: Unchanged(user.id),
+                role: Set(role),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .update(db)
+            .await
+        }
+        None => {
+            CollectionCollaborator::ActiveModel {
+                collection_id: Set(collection.id),
+                user_id: Set(user.id),
+                role: Set(role),
+                inserted_at: Set(now),
+                updated_at: Set(now),
+            }
+            .insert(db)
+            .await
+        }
+    }
+}
+
+pub async fn remove_collaborator(collection_id: i64, user_id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    Ok(CollectionCollaborator::Entity::delete_by_id((collection_id, user_id))
+        .exec(db)
+        .await?
+        .rows_affected
+        == 1)
+}
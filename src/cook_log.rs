@@ -0,0 +1,153 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use entity::cook_log as CookLog;
+use entity::users::Model as User;
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, DbErr, QueryOrder, QuerySelect, TransactionTrait};
+
+use crate::bring::aggregate_weekplan_ingredients;
+use crate::pantry;
+
+#[derive(InputObject)]
+pub struct CookLogInput {
+    /// defaults to the weekplan's date or today
+    pub date: Option<NaiveDate>,
+    #[graphql(validator(minimum = 1, maximum = 5))]
+    pub rating: Option<i32>,
+    #[graphql(validator(max_length = 12288))]
+    pub note: Option<String>,
+}
+
+pub async fn list_cook_log(
+    recipe_id: Option<i64>,
+    limit: u64,
+    offset: u64,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<CookLog::Model>, DbErr> {
+    let mut query = CookLog::Entity::find().filter(CookLog::Column::UserId.eq(user.id));
+
+    if let Some(recipe_id) = recipe_id {
+        query = query.filter(CookLog::Column::RecipeId.eq(recipe_id));
+    }
+
+    query
+        .order_by_desc(CookLog::Column::Date)
+        .order_by_desc(CookLog::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await
+}
+
+pub async fn get_cook_log_entry_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<CookLog::Model>, DbErr> {
+    CookLog::Entity::find_by_id(id).one(db).await
+}
+
+pub async fn log_cooking(
+    recipe_id: i64,
+    values: CookLogInput,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<CookLog::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+
+    CookLog::ActiveModel {
+        user_id: Set(user.id),
+        recipe_id: Set(recipe_id),
+        date: Set(values.date.unwrap_or_else(|| now.date())),
+        rating: Set(values.rating),
+        note: Set(values.note),
+        inserted_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+/// Logs that the recipe of a weekplan entry has been cooked by the user. Logging the same entry again updates the
+/// user's existing log entry. With `deduct_from_pantry` the entry's ingredients are deducted from the user's pantry,
+/// but only when the entry is logged for the first time.
+pub async fn log_weekplan_cooking(
+    weekplan: entity::weekplans::Model,
+    values: CookLogInput,
+    deduct_from_pantry: bool,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<CookLog::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+    let user_id = user.id;
+
+    let existing = CookLog::Entity::find()
+        .filter(CookLog::Column::WeekplanId.eq(weekplan.id))
+        .filter(CookLog::Column::UserId.eq(user_id))
+        .one(db)
+        .await?;
+
+    if let Some(entry) = existing {
+        return CookLog::ActiveModel {
+            id: Unchanged(entry.id),
+            recipe_id: Set(weekplan.recipe_id),
+            date: Set(values.date.unwrap_or(entry.date)),
+            rating: Set(values.rating),
+            note: Set(values.note),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .update(db)
+        .await;
+    }
+
+    let ingredients = if deduct_from_pantry {
+        aggregate_weekplan_ingredients(std::slice::from_ref(&weekplan), db).await?
+    } else {
+        vec![]
+    };
+
+    db.transaction::<_, CookLog::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let entry = CookLog::ActiveModel {
+                user_id: Set(user_id),
+                recipe_id: Set(weekplan.recipe_id),
+                weekplan_id: Set(Some(weekplan.id)),
+                date: Set(values.date.unwrap_or(weekplan.date)),
+                rating: Set(values.rating),
+                note: Set(values.note),
+                inserted_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?;
+
+            pantry::deduct_from_pantry(&ingredients, user_id, txn).await?;
+
+            Ok(entry)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+pub async fn update_cook_log_entry(
+    entry: CookLog::Model,
+    values: CookLogInput,
+    db: &DatabaseConnection,
+) -> Result<CookLog::Model, DbErr> {
+    CookLog::ActiveModel {
+        id: Unchanged(entry.id),
+        date: Set(values.date.unwrap_or(entry.date)),
+        rating: Set(values.rating),
+        note: Set(values.note),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+pub async fn delete_cook_log_entry(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    Ok(CookLog::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1)
+}
@@ -0,0 +1,69 @@
+use entity::users::Model as User;
+use entity::{favorites as Favorite, recipes as Recipe};
+use sea_orm::ActiveValue::Set;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{DatabaseConnection, DbErr, JoinType, QueryOrder, QuerySelect};
+
+use crate::recipes::filter_visible;
+
+/// Restricts the query to the user's favourites
+pub fn filter_favorites(query: Select<Recipe::Entity>, user_id: i64) -> Select<Recipe::Entity> {
+    query.filter(
+        Expr::col((Recipe::Entity, Recipe::Column::Id)).in_subquery(
+            Query::select()
+                .column(Favorite::Column::RecipeId)
+                .from(Favorite::Entity)
+                .and_where(Expr::col(Favorite::Column::UserId).eq(user_id))
+                .to_owned(),
+        ),
+    )
+}
+
+/// The user's favourites still visible to the user, the most recently added first
+pub async fn list_favorite_recipes(
+    limit: u64,
+    offset: u64,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<Recipe::Model>, DbErr> {
+    filter_visible(Recipe::Entity::find(), Some(user.id))
+        .join_rev(JoinType::InnerJoin, Favorite::Relation::Recipes.def())
+        .filter(Favorite::Column::UserId.eq(user.id))
+        .order_by_desc(Favorite::Column::InsertedAt)
+        .order_by_asc(Recipe::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await
+}
+
+/// Bookmarks the recipe; favouring a recipe twice is a no-op
+pub async fn favorite_recipe(
+    recipe: Recipe::Model,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Recipe::Model, DbErr> {
+    let existing = Favorite::Entity::find_by_id((user.id, recipe.id)).one(db).await?;
+
+    if existing.is_none() {
+        Favorite::ActiveModel {
+            user_id: Set(user.id),
+            recipe_id: Set(recipe.id),
+            inserted_at: Set(chrono::Utc::now().naive_utc()),
+        }
+        .insert(db)
+        .await?;
+    }
+
+    Ok(recipe)
+}
+
+pub async fn unfavorite_recipe(
+    recipe: Recipe::Model,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Recipe::Model, DbErr> {
+    Favorite::Entity::delete_by_id((user.id, recipe.id)).exec(db).await?;
+    Ok(recipe)
+}
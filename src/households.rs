@@ -0,0 +1,180 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use async_graphql::*;
+use entity::users::{self, Model as User};
+use entity::{households as Household, shopping_lists as ShoppingList, weekplans as Weekplan};
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, PaginatorTrait, TransactionTrait};
+
+#[derive(InputObject)]
+pub struct HouseholdInput {
+    #[graphql(validator(chars_min_length = 1, chars_max_length = 255))]
+    pub name: String,
+}
+
+pub async fn get_household_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<Household::Model>, DbErr> {
+    Household::Entity::find_by_id(id).one(db).await
+}
+
+/// Creates a household with the user as its only member, named after the user
+pub async fn create_personal_household<C: ConnectionTrait>(
+    name: Option<&str>,
+    email: &str,
+    db: &C,
+) -> Result<Household::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+
+    Household::ActiveModel {
+        name: Set(name.unwrap_or(email).to_owned()),
+        inserted_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn update_household(
+    id: i64,
+    values: HouseholdInput,
+    db: &DatabaseConnection,
+) -> Result<Household::Model, DbErr> {
+    Household::ActiveModel {
+        id: Unchanged(id),
+        name: Set(values.name),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+/// Sets a new invite code, invalidating the previous one
+pub async fn create_invite_code(id: i64, db: &DatabaseConnection) -> Result<Household::Model, DbErr> {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    let code = bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+
+    Household::ActiveModel {
+        id: Unchanged(id),
+        invite_code: Set(Some(code)),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+/// Moves the user into the household with the given invite code. When the user was the last member of their
+/// previous household its weekplans and shopping lists are moved along and the previous household is deleted;
+/// shopping lists for weeks the new household already has a list for are dropped.
+pub async fn join_household(code: String, user: &User, db: &DatabaseConnection) -> Result<Household::Model, DbErr> {
+    let household = Household::Entity::find()
+        .filter(Household::Column::InviteCode.eq(code))
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("Household not found".to_owned()))?;
+
+    if household.id == user.household_id {
+        return Ok(household);
+    }
+
+    let user_id = user.id;
+    let previous_id = user.household_id;
+    let household_id = household.id;
+
+    db.transaction::<_, (), DbErr>(|txn| {
+        Box::pin(async move {
+            set_household(user_id, household_id, txn).await?;
+
+            if member_count(previous_id, txn).await? == 0 {
+                merge_household(previous_id, household_id, txn).await?;
+            }
+
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))?;
+
+    Ok(household)
+}
+
+/// Removes the member from the household, the member gets a new household of their own. The weekplans and shopping
+/// lists stay with the household.
+pub async fn remove_member(
+    household: &Household::Model,
+    user_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Household::Model, DbErr> {
+    let member = users::Entity::find_by_id(user_id)
+        .filter(users::Column::HouseholdId.eq(household.id))
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("Member not found".to_owned()))?;
+
+    if member_count(household.id, db).await? <= 1 {
+        return Err(DbErr::Custom("The last member can't leave the household".to_owned()));
+    }
+
+    db.transaction::<_, (), DbErr>(|txn| {
+        Box::pin(async move {
+            let own = create_personal_household(member.name.as_deref(), &member.email, txn).await?;
+            set_household(member.id, own.id, txn).await?;
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))?;
+
+    Ok(household.clone())
+}
+
+async fn member_count<C: ConnectionTrait>(household_id: i64, db: &C) -> Result<u64, DbErr> {
+    users::Entity::find()
+        .filter(users::Column::HouseholdId.eq(household_id))
+        .count(db)
+        .await
+}
+
+async fn set_household<C: ConnectionTrait>(user_id: i64, household_id: i64, db: &C) -> Result<(), DbErr> {
+    users::ActiveModel {
+        id: Unchanged(user_id),
+        household_id: Set(household_id),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves the weekplans and shopping lists of `from` to `into` and deletes `from`
+async fn merge_household<C: ConnectionTrait>(from: i64, into: i64, db: &C) -> Result<(), DbErr> {
+    Weekplan::Entity::update_many()
+        .col_expr(Weekplan::Column::HouseholdId, Expr::value(into))
+        .filter(Weekplan::Column::HouseholdId.eq(from))
+        .exec(db)
+        .await?;
+
+    ShoppingList::Entity::update_many()
+        .col_expr(ShoppingList::Column::HouseholdId, Expr::value(into))
+        .filter(ShoppingList::Column::HouseholdId.eq(from))
+        .filter(
+            Expr::col(ShoppingList::Column::Week).not_in_subquery(
+                Query::select()
+                    .column(ShoppingList::Column::Week)
+                    .from(ShoppingList::Entity)
+                    .and_where(Expr::col(ShoppingList::Column::HouseholdId).eq(into))
+                    .to_owned(),
+            ),
+        )
+        .exec(db)
+        .await?;
+
+    Household::Entity::delete_by_id(from).exec(db).await?;
+
+    Ok(())
+}
@@ -0,0 +1,266 @@
+//! Parser for free-text ingredient lines like `2 EL Olivenöl, kaltgepresst` or `200 g Mehl`. A line is split into
+//! amount, unit, ingredient name and annotation; the name is fuzzy-matched against the known ingredients.
+
+use std::collections::HashSet;
+
+use async_graphql::SimpleObject;
+use entity::ingredient_units::{Language, Units};
+use entity::{ingredient_units, ingredients, unit_conversion};
+use sea_orm::{ActiveEnum, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Iterable, QueryFilter};
+
+/// Candidates below this confidence are not returned
+const MIN_CONFIDENCE: f64 = 0.4;
+
+#[derive(Clone, Debug, SimpleObject)]
+pub struct IngredientCandidate {
+    pub ingredient: ingredients::Model,
+    /// between 0 and 1, 1 is an exact match of the name
+    pub confidence: f64,
+    /// the line's amount expressed for this ingredient, in `unit` or in the ingredient's reference
+    pub amount: Option<f64>,
+    pub unit: Option<ingredient_units::Model>,
+}
+
+#[derive(Clone, Debug, SimpleObject)]
+pub struct ParsedIngredientLine {
+    pub line: String,
+    pub amount: Option<f64>,
+    pub unit: Option<Units>,
+    pub name: String,
+    pub annotation: Option<String>,
+    /// matching ingredients, best match first
+    pub candidates: Vec<IngredientCandidate>,
+}
+
+fn parse_amount(token: &str) -> Option<f64> {
+    let vulgar = |c| match c {
+        '½' => Some(0.5),
+        '¼' => Some(0.25),
+        '¾' => Some(0.75),
+        '⅓' => Some(1.0 / 3.0),
+        '⅔' => Some(2.0 / 3.0),
+        _ => None,
+    };
+
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        let (numerator, denominator): (f64, f64) = (numerator.parse().ok()?, denominator.parse().ok()?);
+        return (denominator != 0.0).then(|| numerator / denominator);
+    }
+
+    let mut chars = token.chars();
+    match chars.next_back().and_then(vulgar) {
+        Some(fraction) if chars.as_str().is_empty() => Some(fraction),
+        Some(fraction) => chars.as_str().parse::<f64>().ok().map(|n| n + fraction),
+        None => token.replace(',', ".").parse().ok(),
+    }
+}
+
+fn parse_unit(token: &str) -> Option<Units> {
+    let token = token.trim_end_matches('.').to_lowercase();
+
+    match token.as_str() {
+        "el" | "tbs" => return Some(Units::TBSP),
+        "tl" | "tsp" => return Some(Units::TSP),
+        "stk" | "pc" | "pcs" => return Some(Units::PCS),
+        "msp" => return Some(Units::PINCH),
+        "pck" | "pkg" | "päckchen" | "pack" => return Some(Units::PACKET),
+        "zehen" => return Some(Units::CLOVE),
+        "scheiben" => return Some(Units::SLICE),
+        "zweige" => return Some(Units::SPRIG),
+        "dosen" => return Some(Units::CAN),
+        "tassen" => return Some(Units::CUP),
+        "bunde" => return Some(Units::BUNCH),
+        _ => {}
+    }
+
+    Units::iter().find(|unit| {
+        unit.to_value() == token
+            || [Language::De, Language::En].into_iter().any(|language| {
+                let name = unit.display_name(language).to_lowercase();
+                name == token || format!("{}s", name) == token
+            })
+    })
+}
+
+/// Splits a line into amount, unit, name and annotation. Amounts may be decimals (`1,5`), fractions (`1/2`, `½`)
+/// or mixed numbers (`1 1/2`). Everything after the first comma or parenthesis is the annotation.
+pub fn parse_line(line: &str) -> ParsedIngredientLine {
+    let mut tokens = line.split_whitespace().peekable();
+
+    let mut amount: Option<f64> = None;
+    while let Some(value) = tokens.peek().and_then(|token| parse_amount(token)) {
+        amount = Some(amount.unwrap_or(0.0) + value);
+        tokens.next();
+    }
+
+    let unit = match amount {
+        Some(_) => tokens.peek().and_then(|token| parse_unit(token)),
+        None => None,
+    };
+
+    if unit.is_some() {
+        tokens.next();
+    }
+
+    let rest = tokens.collect::<Vec<_>>().join(" ");
+    let (name, annotation) = match rest.find([',', '(']) {
+        Some(idx) => (
+            rest[..idx].trim().to_owned(),
+            Some(rest[idx..].trim_matches(|c: char| c == ',' || c == '(' || c == ')' || c.is_whitespace()))
+                .filter(|annotation| !annotation.is_empty())
+                .map(str::to_owned),
+        ),
+        None => (rest.trim().to_owned(), None),
+    };
+
+    ParsedIngredientLine {
+        line: line.to_owned(),
+        amount,
+        unit,
+        name,
+        annotation,
+        candidates: vec![],
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(s: &str) -> Vec<(char, char)> {
+    let chars = s.chars().collect::<Vec<_>>();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Similarity of two normalized names between 0 and 1: the Sørensen-Dice coefficient of their character bigrams,
+/// raised when the ingredient name is a word of the line (`Olivenöl extra vergine`) or the end of a compound word
+/// (`Weizenmehl` for `Mehl`).
+fn similarity(name: &str, ingredient: &str) -> f64 {
+    if name == ingredient {
+        return 1.0;
+    }
+
+    let (a, b) = (bigrams(name), bigrams(ingredient));
+    let mut score = if a.is_empty() || b.is_empty() {
+        0.0
+    } else {
+        let mut remaining = b.clone();
+        let common = a
+            .iter()
+            .filter(|bigram| {
+                remaining
+                    .iter()
+                    .position(|other| other == *bigram)
+                    .map(|idx| remaining.swap_remove(idx))
+                    .is_some()
+            })
+            .count();
+
+        2.0 * common as f64 / (a.len() + b.len()) as f64
+    };
+
+    let contained = name
+        .split(' ')
+        .any(|word| word == ingredient || word.ends_with(ingredient));
+    if contained && !ingredient.is_empty() {
+        let ratio = ingredient.chars().count() as f64 / name.chars().count() as f64;
+        score = score.max(0.5 + 0.45 * ratio);
+    }
+
+    score.min(1.0)
+}
+
+/// Expresses the amount of a parsed line for an ingredient: in one of the ingredient's units if there is one with
+/// the same identifier (amounts without a unit are taken as pieces), otherwise in the reference if the unit has a
+/// standard size. `None` if the amount can't be expressed for the ingredient.
+fn resolve_amount(
+    parsed: &ParsedIngredientLine,
+    ingredient: &ingredients::Model,
+    units: &[ingredient_units::Model],
+) -> Option<(f64, Option<ingredient_units::Model>)> {
+    let amount = parsed.amount?;
+
+    let identifier = parsed.unit.unwrap_or(Units::PCS);
+    let unit = units
+        .iter()
+        .find(|unit| unit.ingredient_id == ingredient.id && unit.identifier == identifier);
+
+    if let Some(unit) = unit {
+        return Some((amount, Some(unit.clone())));
+    }
+
+    let base_value = unit_conversion::default_base_value(parsed.unit?, ingredient.reference, ingredient.density)?;
+    Some((amount * base_value, None))
+}
+
+/// Parses the lines and finds up to `limit` candidate ingredients for each of them
+pub async fn parse_ingredient_lines(
+    lines: &[String],
+    limit: usize,
+    db: &DatabaseConnection,
+) -> Result<Vec<ParsedIngredientLine>, DbErr> {
+    let ingredients = ingredients::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|ingredient| (normalize(&ingredient.name), ingredient))
+        .collect::<Vec<_>>();
+
+    let mut parsed_lines = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(parse_line)
+        .collect::<Vec<_>>();
+
+    let mut matches = vec![];
+    for parsed in &parsed_lines {
+        let name = normalize(&parsed.name);
+
+        let mut candidates = ingredients
+            .iter()
+            .map(|(ingredient_name, ingredient)| (similarity(&name, ingredient_name), ingredient))
+            .filter(|(confidence, _)| *confidence >= MIN_CONFIDENCE)
+            .collect::<Vec<_>>();
+
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.truncate(limit);
+
+        matches.push(candidates);
+    }
+
+    let ingredient_ids = matches
+        .iter()
+        .flatten()
+        .map(|(_, ingredient)| ingredient.id)
+        .collect::<HashSet<_>>();
+
+    let units = ingredient_units::Entity::find()
+        .filter(ingredient_units::Column::IngredientId.is_in(ingredient_ids))
+        .all(db)
+        .await?;
+
+    for (parsed, candidates) in parsed_lines.iter_mut().zip(matches) {
+        parsed.candidates = candidates
+            .into_iter()
+            .map(|(confidence, ingredient)| {
+                let (amount, unit) = resolve_amount(parsed, ingredient, &units).unzip();
+
+                IngredientCandidate {
+                    ingredient: ingredient.clone(),
+                    confidence,
+                    amount,
+                    unit: unit.flatten(),
+                }
+            })
+            .collect();
+    }
+
+    Ok(parsed_lines)
+}
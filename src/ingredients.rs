@@ -1,17 +1,25 @@
+use std::collections::HashSet;
+
 use async_graphql::*;
 use chrono::Utc;
+use entity::ingredient_allergens::Allergen;
+use entity::unit_conversion::{self, Target};
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::{Expr, Func};
-use sea_orm::ActiveValue::{Set, Unchanged};
-use sea_orm::{Condition, DatabaseConnection, QueryOrder, QuerySelect, TransactionTrait};
+use sea_orm::ActiveValue::{self, Set, Unchanged};
+use sea_orm::{Condition, ConnectionTrait, DatabaseConnection, QueryOrder, QuerySelect, TransactionTrait};
 
 #[derive(SimpleObject, InputObject)]
 pub struct UnitInput {
     id: Option<i64>,
     identifier: entity::ingredient_units::Units,
-    base_value: f64,
+    /// amount of the ingredient's reference (g/ml) in one unit; may be omitted for units with a standard size
+    base_value: Option<f64>,
 }
 
+/// The fields after `alc` are optional. On create omitted ones are 0, `false` or empty. On update omitted fields keep
+/// the stored value, `null` resets them to that default and a given value replaces it; units and allergens are replaced
+/// as a whole.
 #[derive(SimpleObject, InputObject)]
 pub struct IngredientInput {
     #[graphql(validator(chars_min_length = 3, chars_max_length = 255))]
@@ -21,7 +29,28 @@ pub struct IngredientInput {
     fat: f64,
     proteins: f64,
     alc: f64,
-    units: Option<Vec<UnitInput>>,
+    #[graphql(skip_output)]
+    fibre: MaybeUndefined<f64>,
+    #[graphql(skip_output)]
+    sugars: MaybeUndefined<f64>,
+    #[graphql(skip_output)]
+    saturated_fat: MaybeUndefined<f64>,
+    #[graphql(skip_output)]
+    salt: MaybeUndefined<f64>,
+    #[graphql(skip_output)]
+    vegan: MaybeUndefined<bool>,
+    #[graphql(skip_output)]
+    vegetarian: MaybeUndefined<bool>,
+    #[graphql(skip_output)]
+    gluten_free: MaybeUndefined<bool>,
+    #[graphql(skip_output)]
+    lactose_free: MaybeUndefined<bool>,
+    #[graphql(skip_output)]
+    density: MaybeUndefined<f64>,
+    #[graphql(skip_output)]
+    units: MaybeUndefined<Vec<UnitInput>>,
+    #[graphql(skip_output)]
+    allergens: MaybeUndefined<Vec<Allergen>>,
 }
 
 pub async fn list_ingredients(
@@ -97,6 +126,15 @@ pub async fn create_ingredient(
                 fat: Set(ingredient_values.fat),
                 proteins: Set(ingredient_values.proteins),
                 alc: Set(ingredient_values.alc),
+                fibre: Set(ingredient_values.fibre.take().unwrap_or_default()),
+                sugars: Set(ingredient_values.sugars.take().unwrap_or_default()),
+                saturated_fat: Set(ingredient_values.saturated_fat.take().unwrap_or_default()),
+                salt: Set(ingredient_values.salt.take().unwrap_or_default()),
+                vegan: Set(ingredient_values.vegan.take().unwrap_or_default()),
+                vegetarian: Set(ingredient_values.vegetarian.take().unwrap_or_default()),
+                gluten_free: Set(ingredient_values.gluten_free.take().unwrap_or_default()),
+                lactose_free: Set(ingredient_values.lactose_free.take().unwrap_or_default()),
+                density: Set(ingredient_values.density.take()),
                 inserted_at: Set(now),
                 updated_at: Set(now),
                 ..Default::default()
@@ -104,12 +142,14 @@ pub async fn create_ingredient(
             .insert(txn)
             .await?;
 
-            if let Some(units) = ingredient_values.units {
+            if let Some(units) = ingredient_values.units.take() {
                 for unit in units {
+                    let base_value = unit_base_value(&unit, &ingredient)?;
+
                     entity::ingredient_units::ActiveModel {
                         ingredient_id: Set(ingredient.id),
                         identifier: Set(unit.identifier),
-                        base_value: Set(unit.base_value),
+                        base_value: Set(base_value),
                         inserted_at: Set(now),
                         updated_at: Set(now),
                         ..Default::default()
@@ -119,6 +159,10 @@ pub async fn create_ingredient(
                 }
             }
 
+            if let Some(allergens) = ingredient_values.allergens.take() {
+                set_allergens(ingredient.id, allergens, txn).await?;
+            }
+
             Ok(ingredient)
         })
     })
@@ -141,6 +185,15 @@ pub async fn update_ingredient(
         fat: Set(ingredient_values.fat),
         proteins: Set(ingredient_values.proteins),
         alc: Set(ingredient_values.alc),
+        fibre: patched(ingredient_values.fibre),
+        sugars: patched(ingredient_values.sugars),
+        saturated_fat: patched(ingredient_values.saturated_fat),
+        salt: patched(ingredient_values.salt),
+        vegan: patched(ingredient_values.vegan),
+        vegetarian: patched(ingredient_values.vegetarian),
+        gluten_free: patched(ingredient_values.gluten_free),
+        lactose_free: patched(ingredient_values.lactose_free),
+        density: patched(ingredient_values.density.map_value(Some)),
         updated_at: Set(now),
         ..Default::default()
     };
@@ -149,7 +202,8 @@ pub async fn update_ingredient(
         Box::pin(async move {
             let ingredient = values.update(txn).await?;
 
-            if let Some(units) = ingredient_values.units {
+            if !ingredient_values.units.is_undefined() {
+                let units = ingredient_values.units.take().unwrap_or_default();
                 let unit_ids = units.iter().filter_map(|unit| unit.id).collect::<Vec<i64>>();
 
                 entity::ingredient_units::Entity::delete_many()
@@ -159,10 +213,12 @@ pub async fn update_ingredient(
                     .await?;
 
                 for unit in units {
+                    let base_value = unit_base_value(&unit, &ingredient)?;
+
                     let mut unit_values = entity::ingredient_units::ActiveModel {
                         ingredient_id: Set(id),
                         identifier: Set(unit.identifier),
-                        base_value: Set(unit.base_value),
+                        base_value: Set(base_value),
                         updated_at: Set(now),
                         ..Default::default()
                     };
@@ -177,6 +233,10 @@ pub async fn update_ingredient(
                 }
             }
 
+            if !ingredient_values.allergens.is_undefined() {
+                set_allergens(ingredient.id, ingredient_values.allergens.take().unwrap_or_default(), txn).await?;
+            }
+
             Ok(ingredient)
         })
     })
@@ -184,6 +244,73 @@ pub async fn update_ingredient(
     .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
 }
 
+/// Omitted values are kept, `null` resets them to the default
+fn patched<T: Default + Into<sea_orm::Value>>(value: MaybeUndefined<T>) -> ActiveValue<T> {
+    match value {
+        MaybeUndefined::Undefined => ActiveValue::NotSet,
+        MaybeUndefined::Null => Set(T::default()),
+        MaybeUndefined::Value(value) => Set(value),
+    }
+}
+
+async fn set_allergens<C: ConnectionTrait>(ingredient_id: i64, allergens: Vec<Allergen>, db: &C) -> Result<(), DbErr> {
+    entity::ingredient_allergens::Entity::delete_many()
+        .filter(entity::ingredient_allergens::Column::IngredientId.eq(ingredient_id))
+        .exec(db)
+        .await?;
+
+    for allergen in allergens.into_iter().collect::<HashSet<_>>() {
+        entity::ingredient_allergens::ActiveModel {
+            ingredient_id: Set(ingredient_id),
+            allergen: Set(allergen),
+        }
+        .insert(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+fn unit_base_value(unit: &UnitInput, ingredient: &entity::ingredients::Model) -> Result<f64, DbErr> {
+    unit.base_value
+        .or_else(|| unit_conversion::default_base_value(unit.identifier, ingredient.reference, ingredient.density))
+        .ok_or_else(|| DbErr::Custom(format!("Unit {:?} needs a base value", unit.identifier)))
+}
+
+/// Converts an amount of the ingredient given in `unit_id` (or its reference) to either another of its units or to
+/// g/ml. Returns `None` if the units are not compatible.
+pub async fn convert_amount(
+    ingredient: &entity::ingredients::Model,
+    amount: f64,
+    unit_id: Option<i64>,
+    target_unit_id: Option<i64>,
+    target_reference: Option<entity::ingredients::Reference>,
+    db: &DatabaseConnection,
+) -> Result<Option<f64>> {
+    let units = entity::ingredient_units::Entity::find()
+        .filter(entity::ingredient_units::Column::IngredientId.eq(ingredient.id))
+        .all(db)
+        .await?;
+
+    let find_unit = |id: Option<i64>| match id {
+        Some(id) => units
+            .iter()
+            .find(|unit| unit.id == id)
+            .map(Some)
+            .ok_or_else(|| Error::new("Unit not found")),
+        None => Ok(None),
+    };
+
+    let from = find_unit(unit_id)?;
+    let target = match (find_unit(target_unit_id)?, target_reference) {
+        (Some(unit), _) => Target::Unit(unit),
+        (None, Some(reference)) => Target::Reference(reference),
+        (None, None) => Target::Reference(ingredient.reference),
+    };
+
+    Ok(unit_conversion::convert(amount, from, target, ingredient))
+}
+
 pub async fn delete_ingredient(id: i64, db: &DatabaseConnection) -> Result<bool> {
     Ok(entity::ingredients::Entity::delete_by_id(id)
         .exec(db)
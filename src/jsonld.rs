@@ -0,0 +1,589 @@
+//! Export and import of recipes as [schema.org/Recipe](https://schema.org/Recipe) JSON-LD, the format most recipe
+//! websites embed into their pages.
+
+use std::io::Read;
+
+use async_graphql::{SimpleObject, UploadValue};
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router, debug_handler};
+use chrono::Utc;
+use entity::ingredient_units::Language;
+use entity::ingredients::Reference;
+use entity::{ingredient_units, ingredients, steps, steps_ingredients, unit_conversion};
+use http::header;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ingredient_parser::parse_ingredient_lines;
+use crate::types::HttpError;
+use crate::utils::public_url;
+use crate::{AppState, recipes};
+
+pub(crate) fn routes() -> Router<AppState> {
+    Router::new().route("/recipes/{id}/recipe.jsonld", get(get_recipe_jsonld))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonLdRecipe {
+    #[serde(rename = "@context")]
+    pub context: &'static str,
+    #[serde(rename = "@type")]
+    pub kind: &'static str,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub image: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<JsonLdPerson>,
+    pub date_published: String,
+    pub date_modified: String,
+    pub recipe_yield: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prep_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cook_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<String>,
+    pub recipe_ingredient: Vec<String>,
+    pub recipe_instructions: Vec<JsonLdStep>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nutrition: Option<JsonLdNutrition>,
+}
+
+#[derive(Serialize)]
+pub struct JsonLdPerson {
+    #[serde(rename = "@type")]
+    pub kind: &'static str,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct JsonLdStep {
+    #[serde(rename = "@type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub text: String,
+}
+
+/// Nutrition values per serving
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonLdNutrition {
+    #[serde(rename = "@type")]
+    pub kind: &'static str,
+    pub serving_size: String,
+    pub calories: String,
+    pub carbohydrate_content: String,
+    pub fat_content: String,
+    pub protein_content: String,
+    pub fiber_content: String,
+    pub sugar_content: String,
+    pub saturated_fat_content: String,
+    pub sodium_content: String,
+}
+
+#[debug_handler]
+pub async fn get_recipe_jsonld(id: Path<i64>, State(state): State<AppState>) -> Result<impl IntoResponse, HttpError> {
+    let db = &state.conn;
+
+    // the route is public, private recipes are treated like missing ones
+    let recipe = recipes::get_recipe_by_id(*id, db)
+        .await?
+        .filter(|recipe| recipe.visible_to(None))
+        .ok_or_else(|| HttpError::not_found(Some("recipe not found")))?;
+
+    let owner = recipe.find_related(entity::users::Entity).one(db).await?;
+    let tags = recipe.find_related(entity::tags::Entity).all(db).await?;
+
+    let steps = recipe
+        .find_related(steps::Entity)
+        .order_by_asc(steps::Column::Position)
+        .all(db)
+        .await?;
+
+    let step_ingredients = steps_ingredients::Entity::find()
+        .filter(steps_ingredients::Column::StepId.is_in(steps.iter().map(|step| step.id)))
+        .order_by_asc(steps_ingredients::Column::Id)
+        .all(db)
+        .await?;
+
+    let ingredients = ingredients::Entity::find()
+        .filter(ingredients::Column::Id.is_in(step_ingredients.iter().map(|si| si.ingredient_id)))
+        .all(db)
+        .await?;
+
+    let units = ingredient_units::Entity::find()
+        .filter(ingredient_units::Column::Id.is_in(step_ingredients.iter().filter_map(|si| si.unit_id)))
+        .all(db)
+        .await?;
+
+    let calories = entity::recipes::load_calories(vec![recipe.id], db).await?;
+
+    let recipe_ingredient = steps
+        .iter()
+        .flat_map(|step| step_ingredients.iter().filter(move |si| si.step_id == step.id))
+        .filter_map(|si| {
+            let ingredient = ingredients.iter().find(|i| i.id == si.ingredient_id)?;
+            let unit = units.iter().find(|u| Some(u.id) == si.unit_id);
+
+            Some(ingredient_line(si, ingredient, unit, recipe.default_servings))
+        })
+        .collect();
+
+    let preparation_time: i32 = steps.iter().map(|step| step.preparation_time).sum();
+    let cooking_time: i32 = steps.iter().map(|step| step.cooking_time).sum();
+
+    let document = JsonLdRecipe {
+        context: "https://schema.org",
+        kind: "Recipe",
+        image: recipe
+            .image_urls()
+            .map(|image| {
+                [image.original, image.large, image.thumb]
+                    .into_iter()
+                    .map(|url| format!("{}{}", public_url(), url))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        author: owner.map(|owner| JsonLdPerson {
+            kind: "Person",
+            name: owner.name.unwrap_or(owner.email),
+        }),
+        date_published: recipe.inserted_at.and_utc().to_rfc3339(),
+        date_modified: recipe.updated_at.and_utc().to_rfc3339(),
+        recipe_yield: recipe.default_servings.to_string(),
+        prep_time: (preparation_time > 0).then(|| iso_duration(preparation_time)),
+        cook_time: (cooking_time > 0).then(|| iso_duration(cooking_time)),
+        total_time: (preparation_time + cooking_time > 0).then(|| iso_duration(preparation_time + cooking_time)),
+        keywords: (!tags.is_empty()).then(|| tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>().join(", ")),
+        recipe_ingredient,
+        recipe_instructions: steps
+            .into_iter()
+            .map(|step| JsonLdStep {
+                kind: "HowToStep",
+                name: step.name,
+                text: step.description.unwrap_or_default(),
+            })
+            .collect(),
+        nutrition: calories.get(&recipe.id).map(|calories| JsonLdNutrition {
+            kind: "NutritionInformation",
+            serving_size: "1".to_owned(),
+            calories: format!("{:.0} kcal", calories.calories),
+            carbohydrate_content: format!("{:.1} g", calories.carbs),
+            fat_content: format!("{:.1} g", calories.fats),
+            protein_content: format!("{:.1} g", calories.proteins),
+            fiber_content: format!("{:.1} g", calories.fibre),
+            sugar_content: format!("{:.1} g", calories.sugars),
+            saturated_fat_content: format!("{:.1} g", calories.saturated_fat),
+            sodium_content: format!("{:.2} g", calories.salt / entity::ingredients::SALT_PER_SODIUM),
+        }),
+        name: recipe.name,
+        description: recipe.description,
+    };
+
+    Ok(([(header::CONTENT_TYPE, "application/ld+json")], Json(document)))
+}
+
+/// The ingredient line for the whole recipe; the stored amounts are per serving, `recipeYield` is the number of
+/// servings
+fn ingredient_line(
+    si: &steps_ingredients::Model,
+    ingredient: &ingredients::Model,
+    unit: Option<&ingredient_units::Model>,
+    servings: i32,
+) -> String {
+    let amount = si.amount.map(|amount| {
+        unit_conversion::round_amount(unit_conversion::scale(amount, servings), unit.map(|unit| unit.identifier))
+    });
+
+    let mut line = match (amount, unit) {
+        (Some(amount), Some(unit)) => {
+            format!("{} {} {}", amount, unit.identifier.display_name(Language::De), ingredient.name)
+        }
+        (Some(amount), None) => {
+            let reference = match ingredient.reference {
+                Reference::G => "g",
+                Reference::ML => "ml",
+            };
+
+            format!("{} {} {}", amount, reference, ingredient.name)
+        }
+        (None, _) => ingredient.name.clone(),
+    };
+
+    if let Some(annotation) = si.annotation.as_ref().filter(|annotation| !annotation.is_empty()) {
+        line.push_str(", ");
+        line.push_str(annotation);
+    }
+
+    line
+}
+
+/// Formats minutes as ISO 8601 duration, e.g. `PT1H30M`
+fn iso_duration(minutes: i32) -> String {
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+
+    match (hours, minutes) {
+        (0, minutes) => format!("PT{}M", minutes),
+        (hours, 0) => format!("PT{}H", hours),
+        (hours, minutes) => format!("PT{}H{}M", hours, minutes),
+    }
+}
+
+/// Parses an ISO 8601 duration like `PT1H30M` or `P0DT45M` to minutes. Seconds are rounded up to full minutes.
+fn parse_iso_duration(duration: &str) -> Option<i32> {
+    let duration = duration.trim().to_uppercase();
+    let rest = duration.strip_prefix('P')?;
+
+    let mut minutes = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+            _ => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+
+                minutes += match (c, in_time) {
+                    ('W', false) => value * 7.0 * 24.0 * 60.0,
+                    ('D', false) => value * 24.0 * 60.0,
+                    ('H', true) => value * 60.0,
+                    ('M', true) => value,
+                    ('S', true) => value / 60.0,
+                    _ => return None,
+                };
+            }
+        }
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+
+    Some(minutes.ceil() as i32)
+}
+
+#[derive(SimpleObject)]
+pub struct ImportedRecipe {
+    pub recipe: entity::recipes::Model,
+    /// ingredient lines which could not be mapped to an existing ingredient and thus were not imported
+    pub unmatched_ingredients: Vec<String>,
+}
+
+struct ParsedRecipe {
+    name: String,
+    description: Option<String>,
+    servings: i32,
+    preparation_time: i32,
+    cooking_time: i32,
+    tags: Vec<String>,
+    ingredients: Vec<String>,
+    instructions: Vec<(Option<String>, String)>,
+}
+
+/// The number of servings when the document doesn't specify a yield
+const DEFAULT_SERVINGS: i32 = 4;
+
+/// Recipes can't have more tags than this, see `RecipeInput`
+const MAX_TAGS: usize = 5;
+
+/// Ingredient lines are only mapped to their best candidate if it matches at least this well
+const AUTO_MATCH_CONFIDENCE: f64 = 0.8;
+
+fn invalid_document(msg: &str) -> DbErr {
+    DbErr::Custom(format!("invalid JSON-LD document: {}", msg))
+}
+
+/// Reads an uploaded JSON-LD document
+pub fn read_upload(mut upload: UploadValue) -> Result<String, DbErr> {
+    let mut document = String::new();
+
+    upload
+        .content
+        .read_to_string(&mut document)
+        .map_err(|e| invalid_document(&e.to_string()))?;
+
+    Ok(document)
+}
+
+/// Finds the first `Recipe` node in a document, which may be a single node, a list of nodes or a `@graph`
+fn find_recipe(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(nodes) => nodes.iter().find_map(find_recipe),
+        Value::Object(node) => {
+            if node.get("@type").is_some_and(is_recipe_type) {
+                return Some(value);
+            }
+
+            node.get("@graph").and_then(find_recipe)
+        }
+        _ => None,
+    }
+}
+
+fn is_recipe_type(kind: &Value) -> bool {
+    match kind {
+        Value::String(kind) => kind == "Recipe" || kind.ends_with("/Recipe") || kind.ends_with(":Recipe"),
+        Value::Array(kinds) => kinds.iter().any(is_recipe_type),
+        _ => false,
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_owned()).filter(|s| !s.is_empty()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Values like `keywords` can either be a list or a comma separated string
+fn text_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(values)) => values.iter().filter_map(text).collect(),
+        Some(value) => text(value)
+            .map(|s| {
+                s.split(',')
+                    .map(|s| s.trim().to_owned())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        None => vec![],
+    }
+}
+
+fn servings(value: Option<&Value>) -> Option<i32> {
+    match value? {
+        Value::Number(n) => n.as_f64().map(|n| n.round() as i32),
+        Value::String(s) => s
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|s| !s.is_empty())
+            .and_then(|s| s.parse().ok()),
+        Value::Array(values) => values.iter().find_map(|value| servings(Some(value))),
+        _ => None,
+    }
+    .filter(|servings| *servings > 0)
+}
+
+fn instructions(value: Option<&Value>) -> Vec<(Option<String>, String)> {
+    match value {
+        Some(Value::String(s)) => s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| (None, line.to_owned()))
+            .collect(),
+        Some(Value::Array(values)) => values.iter().flat_map(|value| instructions(Some(value))).collect(),
+        Some(Value::Object(node)) => {
+            if let Some(elements) = node.get("itemListElement") {
+                return instructions(Some(elements));
+            }
+
+            let name = node.get("name").and_then(text);
+            match node.get("text").and_then(text) {
+                // many sites repeat the text as the name of a step
+                Some(text) => vec![(name.filter(|name| *name != text), text)],
+                None => name.map(|name| vec![(None, name)]).unwrap_or_default(),
+            }
+        }
+        _ => vec![],
+    }
+}
+
+fn parse_document(document: &str) -> Result<ParsedRecipe, DbErr> {
+    let value: Value = serde_json::from_str(document).map_err(|e| invalid_document(&e.to_string()))?;
+    let recipe = find_recipe(&value).ok_or_else(|| invalid_document("no recipe found"))?;
+
+    let name = recipe
+        .get("name")
+        .and_then(text)
+        .ok_or_else(|| invalid_document("the recipe has no name"))?;
+
+    let mut tags: Vec<String> = vec![];
+    for tag in ["recipeCategory", "recipeCuisine", "keywords"]
+        .into_iter()
+        .flat_map(|key| text_list(recipe.get(key)))
+    {
+        let tag = tag.to_lowercase();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags.truncate(MAX_TAGS);
+
+    let ingredients = recipe.get("recipeIngredient").or_else(|| recipe.get("ingredients"));
+
+    Ok(ParsedRecipe {
+        name,
+        description: recipe.get("description").and_then(text),
+        servings: servings(recipe.get("recipeYield")).unwrap_or(DEFAULT_SERVINGS),
+        preparation_time: recipe
+            .get("prepTime")
+            .and_then(Value::as_str)
+            .and_then(parse_iso_duration)
+            .unwrap_or(0),
+        cooking_time: recipe
+            .get("cookTime")
+            .and_then(Value::as_str)
+            .and_then(parse_iso_duration)
+            .unwrap_or(0),
+        tags,
+        ingredients: match ingredients {
+            Some(Value::Array(lines)) => lines.iter().filter_map(text).collect(),
+            Some(line) => text(line).into_iter().collect(),
+            None => vec![],
+        },
+        instructions: instructions(recipe.get("recipeInstructions")),
+    })
+}
+
+/// Imports a JSON-LD document as new recipe. Ingredients are mapped to existing ingredients by name (see
+/// `ingredient_parser`), missing tags are created. Everything is created in one transaction.
+pub async fn import_recipe(document: &str, owner_id: i64, db: &DatabaseConnection) -> Result<ImportedRecipe, DbErr> {
+    let parsed = parse_document(document)?;
+
+    let lines = parse_ingredient_lines(&parsed.ingredients, 1, db).await?;
+
+    let existing_tags = entity::tags::Entity::find()
+        .filter(entity::tags::Column::Name.is_in(parsed.tags.clone()))
+        .all(db)
+        .await?;
+
+    let mut unmatched_ingredients = vec![];
+    let mut step_ingredients = vec![];
+
+    for line in lines {
+        let Some(candidate) = line
+            .candidates
+            .first()
+            .filter(|candidate| candidate.confidence >= AUTO_MATCH_CONFIDENCE)
+        else {
+            unmatched_ingredients.push(line.line);
+            continue;
+        };
+
+        let annotation = match (candidate.amount, line.amount) {
+            (None, Some(_)) => Some(line.line.clone()),
+            _ => line.annotation.clone(),
+        };
+
+        // the document's amounts are for the whole yield, they are stored per serving
+        step_ingredients.push((
+            candidate.ingredient.id,
+            candidate.amount.map(|amount| amount / parsed.servings as f64),
+            candidate.unit.as_ref().map(|unit| unit.id),
+            annotation,
+        ));
+    }
+
+    let recipe = db
+        .transaction::<_, entity::recipes::Model, DbErr>(|txn| {
+            Box::pin(async move {
+                let now = Utc::now().naive_utc();
+
+                let recipe = entity::recipes::ActiveModel {
+                    name: Set(parsed.name),
+                    default_servings: Set(parsed.servings),
+                    description: Set(parsed.description),
+                    owner_id: Set(Some(owner_id)),
+                    inserted_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(txn)
+                .await?;
+
+                for name in parsed.tags {
+                    let tag_id = match existing_tags.iter().find(|tag| tag.name == name) {
+                        Some(tag) => tag.id,
+                        None => {
+                            entity::tags::ActiveModel {
+                                name: Set(name),
+                                inserted_at: Set(now),
+                                updated_at: Set(now),
+                                ..Default::default()
+                            }
+                            .insert(txn)
+                            .await?
+                            .id
+                        }
+                    };
+
+                    entity::recipes_tags::ActiveModel {
+                        recipe_id: Set(recipe.id),
+                        tag_id: Set(tag_id),
+                    }
+                    .insert(txn)
+                    .await?;
+                }
+
+                let mut instructions = parsed.instructions;
+                if instructions.is_empty() && !step_ingredients.is_empty() {
+                    instructions.push((None, String::new()));
+                }
+
+                // schema.org doesn't assign ingredients or times to steps, so everything goes to the first one
+                for (position, (name, text)) in instructions.into_iter().enumerate() {
+                    let first = position == 0;
+
+                    let step = steps::ActiveModel {
+                        name: Set(name),
+                        recipe_id: Set(recipe.id),
+                        position: Set(position as i32),
+                        description: Set(Some(text).filter(|text| !text.is_empty())),
+                        preparation_time: Set(if first { parsed.preparation_time } else { 0 }),
+                        cooking_time: Set(if first { parsed.cooking_time } else { 0 }),
+                        inserted_at: Set(now),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    }
+                    .insert(txn)
+                    .await?;
+
+                    if !first {
+                        continue;
+                    }
+
+                    for (ingredient_id, amount, unit_id, annotation) in &step_ingredients {
+                        steps_ingredients::ActiveModel {
+                            step_id: Set(step.id),
+                            ingredient_id: Set(*ingredient_id),
+                            amount: Set(*amount),
+                            unit_id: Set(*unit_id),
+                            annotation: Set(annotation.clone()),
+                            inserted_at: Set(now),
+                            updated_at: Set(now),
+                            ..Default::default()
+                        }
+                        .insert(txn)
+                        .await?;
+                    }
+                }
+
+                Ok(recipe)
+            })
+        })
+        .await
+        .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))?;
+
+    Ok(ImportedRecipe {
+        recipe,
+        unmatched_ingredients,
+    })
+}
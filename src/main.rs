@@ -17,15 +17,29 @@ use crate::types::AppState;
 mod api;
 mod authorization;
 mod bring;
+mod calendar;
+mod collections;
+mod cook_log;
 mod current_user;
+mod favorites;
+mod households;
+mod ingredient_parser;
 mod ingredients;
+mod jsonld;
+mod nutrition;
+mod pantry;
+mod pictures;
+mod recipe_comments;
+mod recipe_revisions;
 mod recipes;
+mod shopping_lists;
 mod steps;
 mod tags;
 mod types;
 mod users;
 mod utils;
 mod weekplan;
+mod weekplan_generator;
 
 async fn index(
     Extension(schema): Extension<api::RecipesSchema>,
@@ -60,16 +74,17 @@ async fn main() {
 
     let schema = api::create_schema(conn.clone());
     let state = AppState { conn, token_key };
-    let pictures_static_path = utils::image_base_path();
     let avatars_static_path = utils::avatar_base_path();
 
     let listener = TcpListener::bind(addrs).await.expect("could not create listener");
 
     let mut router = Router::new()
-        .nest_service("/pictures", ServeDir::new(pictures_static_path))
+        .nest("/pictures", pictures::routes())
         .nest_service("/avatars", ServeDir::new(avatars_static_path))
         .route("/graphql", get(index_graphiql).post(index))
-        .merge(bring::routes());
+        .merge(bring::routes())
+        .merge(calendar::routes())
+        .merge(jsonld::routes());
 
     router = router
         .layer(Extension(schema))
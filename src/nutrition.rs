@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use async_graphql::*;
+use chrono::NaiveDate;
+use entity::nutrition_targets as NutritionTargets;
+use entity::recipes::CaloriesResult;
+use entity::users::Model as User;
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, DbErr};
+
+/// Days deviating from a target by more than this fraction are flagged
+const DEFAULT_TOLERANCE: f64 = 0.2;
+
+#[derive(InputObject)]
+pub struct NutritionTargetsInput {
+    #[graphql(validator(minimum = 0))]
+    pub calories: Option<f64>,
+    #[graphql(validator(minimum = 0))]
+    pub proteins: Option<f64>,
+    #[graphql(validator(minimum = 0))]
+    pub carbs: Option<f64>,
+    #[graphql(validator(minimum = 0))]
+    pub fats: Option<f64>,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Nutrient {
+    Calories,
+    Proteins,
+    Carbs,
+    Fats,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NutritionStatus {
+    Over,
+    Under,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct NutritionFlag {
+    pub nutrient: Nutrient,
+    pub status: NutritionStatus,
+    /// relative deviation from the target, e.g. 0.35 for 35% over the target
+    pub deviation: f64,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct DayNutrition {
+    pub date: NaiveDate,
+    /// all portions eaten on the day
+    pub nutrition: CaloriesResult,
+    /// what one person eats on the day: one serving of every planned meal
+    pub per_person: CaloriesResult,
+    /// nutrients of one person's day deviating from the user's targets by more than the tolerance
+    pub flags: Vec<NutritionFlag>,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct WeekplanNutrition {
+    /// only days with planned recipes
+    pub days: Vec<DayNutrition>,
+    /// all portions eaten in the week
+    pub total: CaloriesResult,
+    pub targets: Option<NutritionTargets::Model>,
+}
+
+pub async fn get_nutrition_targets(
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Option<NutritionTargets::Model>, DbErr> {
+    NutritionTargets::Entity::find()
+        .filter(NutritionTargets::Column::UserId.eq(user.id))
+        .one(db)
+        .await
+}
+
+pub async fn set_nutrition_targets(
+    values: NutritionTargetsInput,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<NutritionTargets::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+
+    match get_nutrition_targets(user, db).await? {
+        Some(targets) => {
+            NutritionTargets::ActiveModel {
+                id: Unchanged(targets.id),
+                calories: Set(values.calories),
+                proteins: Set(values.proteins),
+                carbs: Set(values.carbs),
+                fats: Set(values.fats),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .update(db)
+            .await
+        }
+        None => {
+            NutritionTargets::ActiveModel {
+                user_id: Set(user.id),
+                calories: Set(values.calories),
+                proteins: Set(values.proteins),
+                carbs: Set(values.carbs),
+                fats: Set(values.fats),
+                inserted_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(db)
+            .await
+        }
+    }
+}
+
+fn flags(nutrition: &CaloriesResult, targets: &NutritionTargets::Model, tolerance: f64) -> Vec<NutritionFlag> {
+    [
+        (Nutrient::Calories, nutrition.calories, targets.calories),
+        (Nutrient::Proteins, nutrition.proteins, targets.proteins),
+        (Nutrient::Carbs, nutrition.carbs, targets.carbs),
+        (Nutrient::Fats, nutrition.fats, targets.fats),
+    ]
+    .into_iter()
+    .filter_map(|(nutrient, value, target)| {
+        let target = target.filter(|target| *target > 0.0)?;
+        let deviation = (value - target) / target;
+
+        if deviation.abs() <= tolerance {
+            return None;
+        }
+
+        Some(NutritionFlag {
+            nutrient,
+            status: if deviation > 0.0 {
+                NutritionStatus::Over
+            } else {
+                NutritionStatus::Under
+            },
+            deviation,
+        })
+    })
+    .collect()
+}
+
+/// Sums up the nutrition values of the week's entries per day, scaled by the entries' portions. Leftovers count on the
+/// day they are eaten, so the entry they were cooked with only counts with its own portions. Besides that, every entry
+/// counts with one serving per day for one person, this is what the user's daily targets are compared with.
+pub async fn weekplan_nutrition(
+    week: &NaiveDate,
+    tolerance: Option<f64>,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<WeekplanNutrition, DbErr> {
+    let weekplan = crate::weekplan::list_weekplan(week, None, user, db).await?;
+    let recipe_ids = weekplan.iter().map(|entry| entry.recipe_id).collect::<Vec<_>>();
+    let calories = entity::recipes::load_calories(recipe_ids, db).await?;
+    let portions = crate::weekplan::own_portions(&weekplan, db).await?;
+
+    let mut days: BTreeMap<NaiveDate, (CaloriesResult, CaloriesResult)> = BTreeMap::new();
+    for entry in &weekplan {
+        let (day, per_person) = days.entry(entry.date).or_default();
+
+        if let Some(calories) = calories.get(&entry.recipe_id) {
+            day.add(&calories.scale(portions[&entry.id] as f64));
+            per_person.add(calories);
+        }
+    }
+
+    let targets = get_nutrition_targets(user, db).await?;
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+    let mut total = CaloriesResult::default();
+
+    let days = days
+        .into_iter()
+        .map(|(date, (nutrition, per_person))| {
+            total.add(&nutrition);
+
+            DayNutrition {
+                date,
+                flags: targets
+                    .as_ref()
+                    .map(|targets| flags(&per_person, targets, tolerance))
+                    .unwrap_or_default(),
+                nutrition,
+                per_person,
+            }
+        })
+        .collect();
+
+    Ok(WeekplanNutrition { days, total, targets })
+}
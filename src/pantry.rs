@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use async_graphql::*;
+use chrono::NaiveDate;
+use entity::users::Model as User;
+use entity::{ingredient_units, pantry_items as PantryItem, unit_conversion};
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::NullOrdering;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Order, QueryOrder};
+
+use crate::bring::BringInfo;
+
+#[derive(InputObject)]
+pub struct PantryItemInput {
+    pub ingredient_id: i64,
+    /// one of the ingredient's units, the amount is given in the ingredient's reference (g/ml) when empty
+    pub unit_id: Option<i64>,
+    #[graphql(validator(minimum = 0))]
+    pub amount: f64,
+    pub best_before: Option<NaiveDate>,
+}
+
+/// The user's pantry, items expiring first
+pub async fn list_pantry_items(user: &User, db: &DatabaseConnection) -> Result<Vec<PantryItem::Model>, DbErr> {
+    PantryItem::Entity::find()
+        .filter(PantryItem::Column::UserId.eq(user.id))
+        .order_by_with_nulls(PantryItem::Column::BestBefore, Order::Asc, NullOrdering::Last)
+        .order_by_asc(PantryItem::Column::Id)
+        .all(db)
+        .await
+}
+
+/// The ingredients the user has in stock
+pub async fn pantry_ingredient_ids(user: &User, db: &DatabaseConnection) -> Result<Vec<i64>, DbErr> {
+    let items = PantryItem::Entity::find()
+        .filter(PantryItem::Column::UserId.eq(user.id))
+        .filter(PantryItem::Column::Amount.gt(0.0))
+        .all(db)
+        .await?;
+
+    Ok(items.into_iter().map(|item| item.ingredient_id).collect())
+}
+
+pub async fn get_pantry_item_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<PantryItem::Model>, DbErr> {
+    PantryItem::Entity::find_by_id(id).one(db).await
+}
+
+async fn validate_unit(values: &PantryItemInput, db: &DatabaseConnection) -> Result<(), DbErr> {
+    let Some(unit_id) = values.unit_id else {
+        return Ok(());
+    };
+
+    let unit = ingredient_units::Entity::find_by_id(unit_id).one(db).await?;
+
+    match unit {
+        Some(unit) if unit.ingredient_id == values.ingredient_id => Ok(()),
+        _ => Err(DbErr::Custom(format!("Unit {} does not belong to ingredient {}", unit_id, values.ingredient_id))),
+    }
+}
+
+pub async fn create_pantry_item(
+    values: PantryItemInput,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<PantryItem::Model, DbErr> {
+    validate_unit(&values, db).await?;
+    let now = chrono::Utc::now().naive_utc();
+
+    PantryItem::ActiveModel {
+        user_id: Set(user.id),
+        ingredient_id: Set(values.ingredient_id),
+        unit_id: Set(values.unit_id),
+        amount: Set(values.amount),
+        best_before: Set(values.best_before),
+        inserted_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn update_pantry_item(
+    id: i64,
+    values: PantryItemInput,
+    db: &DatabaseConnection,
+) -> Result<PantryItem::Model, DbErr> {
+    validate_unit(&values, db).await?;
+
+    PantryItem::ActiveModel {
+        id: Unchanged(id),
+        ingredient_id: Set(values.ingredient_id),
+        unit_id: Set(values.unit_id),
+        amount: Set(values.amount),
+        best_before: Set(values.best_before),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+pub async fn delete_pantry_item(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    Ok(PantryItem::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1)
+}
+
+/// The user's pantry items together with their units, items expiring first
+async fn pantry_with_units<C: ConnectionTrait>(
+    user_id: i64,
+    db: &C,
+) -> Result<Vec<(PantryItem::Model, Option<ingredient_units::Model>)>, DbErr> {
+    PantryItem::Entity::find()
+        .filter(PantryItem::Column::UserId.eq(user_id))
+        .order_by_with_nulls(PantryItem::Column::BestBefore, Order::Asc, NullOrdering::Last)
+        .order_by_asc(PantryItem::Column::Id)
+        .find_also_related(ingredient_units::Entity)
+        .all(db)
+        .await
+}
+
+/// Subtracts the user's stock from the aggregated ingredients; ingredients fully in stock are removed. Ingredients
+/// without an amount are removed as soon as there is any stock of them.
+pub async fn subtract_stock(
+    ingredients: Vec<BringInfo>,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<BringInfo>, DbErr> {
+    let mut stock: HashMap<i64, f64> = HashMap::new();
+
+    for (item, unit) in pantry_with_units(user.id, db).await? {
+        *stock.entry(item.ingredient_id).or_default() +=
+            unit_conversion::to_reference(item.amount, unit.map(|unit| unit.base_value));
+    }
+
+    Ok(ingredients
+        .into_iter()
+        .filter_map(|mut info| {
+            let Some(in_stock) = stock.get(&info.ingredient.id).copied().filter(|amount| *amount > 0.0) else {
+                return Some(info);
+            };
+
+            let base_value = info.unit.as_ref().map(|unit| unit.base_value);
+            let needed = unit_conversion::to_reference(info.amount, base_value);
+
+            if needed <= in_stock {
+                return None;
+            }
+
+            let missing = needed - in_stock;
+            info.amount = base_value
+                .filter(|value| *value > 0.0)
+                .map_or(missing, |value| missing / value);
+
+            Some(info)
+        })
+        .collect())
+}
+
+/// Deducts the ingredients from the user's pantry, starting with the items expiring first. Items used up are
+/// deleted, missing stock is ignored.
+pub async fn deduct_from_pantry<C: ConnectionTrait>(
+    ingredients: &[BringInfo],
+    user_id: i64,
+    db: &C,
+) -> Result<(), DbErr> {
+    let mut needed = ingredients
+        .iter()
+        .map(|info| {
+            let amount = unit_conversion::to_reference(info.amount, info.unit.as_ref().map(|unit| unit.base_value));
+            (info.ingredient.id, amount)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let now = chrono::Utc::now().naive_utc();
+
+    for (item, unit) in pantry_with_units(user_id, db).await? {
+        let Some(needed) = needed.get_mut(&item.ingredient_id).filter(|amount| **amount > 0.0) else {
+            continue;
+        };
+
+        let base_value = unit.map(|unit| unit.base_value).filter(|value| *value > 0.0);
+        let available = unit_conversion::to_reference(item.amount, base_value);
+
+        if available <= *needed {
+            *needed -= available;
+            PantryItem::Entity::delete_by_id(item.id).exec(db).await?;
+            continue;
+        }
+
+        let remaining = available - *needed;
+        *needed = 0.0;
+
+        PantryItem::ActiveModel {
+            id: Unchanged(item.id),
+            amount: Set(base_value.map_or(remaining, |value| remaining / value)),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! The uploaded pictures. Pictures of recipes are only served to users the recipe is visible to, collection covers
+//! are served as they are.
+
+use std::path::{Component, Path as FsPath};
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::{Extension, Path, Request, State};
+use axum::response::Response;
+use axum::routing::get;
+use entity::users::Model as User;
+use tower_http::services::ServeFile;
+
+use crate::types::HttpError;
+use crate::utils::image_base_path;
+use crate::{AppState, recipes};
+
+pub(crate) fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/collections/{collection_id}/{file}", get(get_collection_cover))
+        .route("/{recipe_id}/{file}", get(get_recipe_picture))
+}
+
+/// Pictures of a recipe are stored in a directory named by the recipe's id, see `recipes::save_picture`. Pictures
+/// of private recipes are treated like missing ones for everyone else.
+async fn get_recipe_picture(
+    Path((recipe_id, file)): Path<(i64, String)>,
+    Extension(user): Extension<Option<User>>,
+    State(state): State<AppState>,
+    request: Request,
+) -> Result<Response, HttpError> {
+    recipes::get_recipe_by_id(recipe_id, &state.conn)
+        .await?
+        .filter(|recipe| recipe.visible_to(user.as_ref()))
+        .ok_or_else(|| HttpError::not_found(Some("picture not found")))?;
+
+    serve_picture(format!("{}/{}", image_base_path(), recipe_id), &file, request).await
+}
+
+async fn get_collection_cover(
+    Path((collection_id, file)): Path<(i64, String)>,
+    request: Request,
+) -> Result<Response, HttpError> {
+    serve_picture(format!("{}/collections/{}", image_base_path(), collection_id), &file, request).await
+}
+
+/// Serves a file of the picture directory; the file name is a single path component, so nothing outside the
+/// directory can be reached
+async fn serve_picture(dir: String, file: &str, request: Request) -> Result<Response, HttpError> {
+    let mut components = FsPath::new(file).components();
+
+    if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+        return Err(HttpError::not_found(Some("picture not found")));
+    }
+
+    let response = ServeFile::new(FsPath::new(&dir).join(file))
+        .try_call(request)
+        .await
+        .map_err(|_| HttpError::not_found(Some("picture not found")))?;
+
+    Ok(response.map(Body::new))
+}
@@ -0,0 +1,90 @@
+use async_graphql::*;
+use entity::recipe_comments as Comment;
+use entity::users::Model as User;
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, DbErr, QueryOrder, QuerySelect};
+
+#[derive(InputObject)]
+pub struct RecipeCommentInput {
+    #[graphql(validator(chars_min_length = 1, chars_max_length = 12288))]
+    pub body: String,
+}
+
+/// The comments of a recipe, oldest first; the top level comments when `parent_id` is empty, otherwise the replies
+/// to the given comment
+pub async fn list_comments(
+    recipe_id: i64,
+    parent_id: Option<i64>,
+    limit: u64,
+    offset: u64,
+    db: &DatabaseConnection,
+) -> Result<Vec<Comment::Model>, DbErr> {
+    let query = Comment::Entity::find().filter(Comment::Column::RecipeId.eq(recipe_id));
+
+    let query = match parent_id {
+        Some(parent_id) => query.filter(Comment::Column::ParentId.eq(parent_id)),
+        None => query.filter(Comment::Column::ParentId.is_null()),
+    };
+
+    query
+        .order_by_asc(Comment::Column::InsertedAt)
+        .order_by_asc(Comment::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await
+}
+
+pub async fn get_comment_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<Comment::Model>, DbErr> {
+    Comment::Entity::find_by_id(id).one(db).await
+}
+
+/// Creates a comment; replies have to belong to the same recipe as the comment they reply to
+pub async fn create_comment(
+    recipe_id: i64,
+    parent_id: Option<i64>,
+    values: RecipeCommentInput,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Comment::Model, DbErr> {
+    if let Some(parent_id) = parent_id {
+        let parent = get_comment_by_id(parent_id, db).await?;
+
+        if parent.is_none_or(|parent| parent.recipe_id != recipe_id) {
+            return Err(DbErr::RecordNotFound("Parent comment not found".to_owned()));
+        }
+    }
+
+    Comment::ActiveModel {
+        recipe_id: Set(recipe_id),
+        author_id: Set(user.id),
+        parent_id: Set(parent_id),
+        body: Set(values.body),
+        inserted_at: Set(chrono::Utc::now().naive_utc()),
+        edited_at: Set(None),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn update_comment(
+    id: i64,
+    values: RecipeCommentInput,
+    db: &DatabaseConnection,
+) -> Result<Comment::Model, DbErr> {
+    Comment::ActiveModel {
+        id: Unchanged(id),
+        body: Set(values.body),
+        edited_at: Set(Some(chrono::Utc::now().naive_utc())),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+/// Deletes the comment together with all replies to it
+pub async fn delete_comment(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    Ok(Comment::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1)
+}
@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+
+use async_graphql::*;
+use entity::recipe_revisions::{self as RecipeRevision, RecipeSnapshot, StepIngredientSnapshot, StepSnapshot};
+use entity::{ingredient_units, ingredients, recipes, recipes_tags, steps, steps_ingredients, tags};
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, QueryOrder, TransactionTrait};
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct FieldChange {
+    /// the field's GraphQL name, e.g. `defaultServings`
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct StepIngredientChange {
+    pub ingredient_id: i64,
+    pub change: ChangeKind,
+    pub old: Option<StepIngredientSnapshot>,
+    pub new: Option<StepIngredientSnapshot>,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct StepChange {
+    /// the step's index in the recipe, steps are compared by their order
+    pub index: i32,
+    pub change: ChangeKind,
+    pub fields: Vec<FieldChange>,
+    pub ingredients: Vec<StepIngredientChange>,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct RecipeDiff {
+    pub fields: Vec<FieldChange>,
+    pub added_tag_ids: Vec<i64>,
+    pub removed_tag_ids: Vec<i64>,
+    /// only steps with changes
+    pub steps: Vec<StepChange>,
+}
+
+pub async fn get_revision_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<RecipeRevision::Model>, DbErr> {
+    RecipeRevision::Entity::find_by_id(id).one(db).await
+}
+
+/// The current state of the recipe
+pub async fn take_snapshot<C: ConnectionTrait>(recipe_id: i64, db: &C) -> Result<Option<RecipeSnapshot>, DbErr> {
+    let Some(recipe) = recipes::Entity::find_by_id(recipe_id).one(db).await? else {
+        return Ok(None);
+    };
+
+    let tag_ids = recipes_tags::Entity::find()
+        .filter(recipes_tags::Column::RecipeId.eq(recipe_id))
+        .order_by_asc(recipes_tags::Column::TagId)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|recipe_tag| recipe_tag.tag_id)
+        .collect();
+
+    let steps = steps::Entity::find()
+        .filter(steps::Column::RecipeId.eq(recipe_id))
+        .order_by_asc(steps::Column::Position)
+        .order_by_asc(steps::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut step_ingredients: HashMap<i64, Vec<StepIngredientSnapshot>> = HashMap::new();
+
+    for step_ingredient in steps_ingredients::Entity::find()
+        .filter(steps_ingredients::Column::StepId.is_in(steps.iter().map(|step| step.id)))
+        .order_by_asc(steps_ingredients::Column::Id)
+        .all(db)
+        .await?
+    {
+        step_ingredients
+            .entry(step_ingredient.step_id)
+            .or_default()
+            .push(StepIngredientSnapshot {
+                ingredient_id: step_ingredient.ingredient_id,
+                amount: step_ingredient.amount,
+                annotation: step_ingredient.annotation,
+                unit_id: step_ingredient.unit_id,
+            });
+    }
+
+    Ok(Some(RecipeSnapshot {
+        name: recipe.name,
+        default_servings: recipe.default_servings,
+        description: recipe.description,
+        tag_ids,
+        steps: steps
+            .into_iter()
+            .map(|step| StepSnapshot {
+                ingredients: step_ingredients.remove(&step.id).unwrap_or_default(),
+                name: step.name,
+                position: step.position,
+                description: step.description,
+                preparation_time: step.preparation_time,
+                cooking_time: step.cooking_time,
+            })
+            .collect(),
+    }))
+}
+
+/// Stores the current state of the recipe as a revision; has to be called right before changing the recipe. Nothing
+/// is stored when the state equals the latest revision.
+pub async fn record_revision<C: ConnectionTrait>(recipe_id: i64, db: &C) -> Result<(), DbErr> {
+    let Some(snapshot) = take_snapshot(recipe_id, db).await? else {
+        return Ok(());
+    };
+
+    let latest = RecipeRevision::Entity::find()
+        .filter(RecipeRevision::Column::RecipeId.eq(recipe_id))
+        .order_by_desc(RecipeRevision::Column::Id)
+        .one(db)
+        .await?;
+
+    if let Some(latest) = latest {
+        if latest.recipe_snapshot().is_ok_and(|latest| latest == snapshot) {
+            return Ok(());
+        }
+    }
+
+    let snapshot = serde_json::to_value(&snapshot).map_err(|e| DbErr::Json(e.to_string()))?;
+
+    RecipeRevision::ActiveModel {
+        recipe_id: Set(recipe_id),
+        snapshot: Set(snapshot),
+        inserted_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Resets the recipe to the state of the revision. The current state is recorded as a revision before, so restoring
+/// can be undone. Tags, ingredients and units deleted in the meantime are left out.
+pub async fn restore_revision(
+    revision: RecipeRevision::Model,
+    db: &DatabaseConnection,
+) -> Result<recipes::Model, DbErr> {
+    let snapshot = revision.recipe_snapshot()?;
+    let recipe_id = revision.recipe_id;
+    let now = chrono::Utc::now().naive_utc();
+
+    db.transaction::<_, recipes::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            record_revision(recipe_id, txn).await?;
+
+            let recipe = recipes::ActiveModel {
+                id: Unchanged(recipe_id),
+                name: Set(snapshot.name),
+                default_servings: Set(snapshot.default_servings),
+                description: Set(snapshot.description),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .update(txn)
+            .await?;
+
+            recipes_tags::Entity::delete_many()
+                .filter(recipes_tags::Column::RecipeId.eq(recipe_id))
+                .exec(txn)
+                .await?;
+
+            for tag in tags::Entity::find()
+                .filter(tags::Column::Id.is_in(snapshot.tag_ids))
+                .all(txn)
+                .await?
+            {
+                recipes_tags::ActiveModel {
+                    recipe_id: Set(recipe_id),
+                    tag_id: Set(tag.id),
+                }
+                .insert(txn)
+                .await?;
+            }
+
+            // step ingredients are deleted by the foreign key
+            steps::Entity::delete_many()
+                .filter(steps::Column::RecipeId.eq(recipe_id))
+                .exec(txn)
+                .await?;
+
+            let ingredient_ids = snapshot
+                .steps
+                .iter()
+                .flat_map(|step| step.ingredients.iter().map(|ingredient| ingredient.ingredient_id))
+                .collect::<Vec<_>>();
+            let unit_ids = snapshot
+                .steps
+                .iter()
+                .flat_map(|step| step.ingredients.iter().filter_map(|ingredient| ingredient.unit_id))
+                .collect::<Vec<_>>();
+
+            let existing_ingredients = ingredients::Entity::find()
+                .filter(ingredients::Column::Id.is_in(ingredient_ids))
+                .all(txn)
+                .await?
+                .into_iter()
+                .map(|ingredient| ingredient.id)
+                .collect::<Vec<_>>();
+            let existing_units = ingredient_units::Entity::find()
+                .filter(ingredient_units::Column::Id.is_in(unit_ids))
+                .all(txn)
+                .await?
+                .into_iter()
+                .map(|unit| unit.id)
+                .collect::<Vec<_>>();
+
+            for step_values in snapshot.steps {
+                let step = steps::ActiveModel {
+                    name: Set(step_values.name),
+                    recipe_id: Set(recipe_id),
+                    position: Set(step_values.position),
+                    description: Set(step_values.description),
+                    preparation_time: Set(step_values.preparation_time),
+                    cooking_time: Set(step_values.cooking_time),
+                    inserted_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(txn)
+                .await?;
+
+                for ingredient in step_values.ingredients {
+                    if !existing_ingredients.contains(&ingredient.ingredient_id) {
+                        continue;
+                    }
+
+                    steps_ingredients::ActiveModel {
+                        step_id: Set(step.id),
+                        ingredient_id: Set(ingredient.ingredient_id),
+                        amount: Set(ingredient.amount),
+                        annotation: Set(ingredient.annotation),
+                        unit_id: Set(ingredient.unit_id.filter(|unit_id| existing_units.contains(unit_id))),
+                        inserted_at: Set(now),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    }
+                    .insert(txn)
+                    .await?;
+                }
+            }
+
+            Ok(recipe)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+/// Compares the `from` revision with the `to` revision, or with the current state of the recipe when `to` is empty
+pub async fn diff_revisions(
+    from: &RecipeRevision::Model,
+    to: Option<&RecipeRevision::Model>,
+    db: &DatabaseConnection,
+) -> Result<RecipeDiff, DbErr> {
+    let old = from.recipe_snapshot()?;
+    let new = match to {
+        Some(to) => to.recipe_snapshot()?,
+        None => take_snapshot(from.recipe_id, db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Recipe not found".to_owned()))?,
+    };
+
+    Ok(diff_snapshots(&old, &new))
+}
+
+fn field_change<T: PartialEq + ToString>(field: &str, old: Option<T>, new: Option<T>) -> Option<FieldChange> {
+    if old == new {
+        return None;
+    }
+
+    Some(FieldChange {
+        field: field.to_owned(),
+        old: old.map(|value| value.to_string()),
+        new: new.map(|value| value.to_string()),
+    })
+}
+
+fn step_fields(old: Option<&StepSnapshot>, new: Option<&StepSnapshot>) -> Vec<FieldChange> {
+    [
+        field_change("name", old.and_then(|s| s.name.clone()), new.and_then(|s| s.name.clone())),
+        field_change("description", old.and_then(|s| s.description.clone()), new.and_then(|s| s.description.clone())),
+        field_change("preparationTime", old.map(|s| s.preparation_time), new.map(|s| s.preparation_time)),
+        field_change("cookingTime", old.map(|s| s.cooking_time), new.map(|s| s.cooking_time)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Ingredients are matched by their id; an ingredient used more than once in a step is matched in order
+fn step_ingredient_changes(
+    old: &[StepIngredientSnapshot],
+    new: &[StepIngredientSnapshot],
+) -> Vec<StepIngredientChange> {
+    let mut changes = vec![];
+    let mut unmatched = new.iter().collect::<Vec<_>>();
+
+    for old in old {
+        let matching = unmatched
+            .iter()
+            .position(|new| new.ingredient_id == old.ingredient_id)
+            .map(|index| unmatched.remove(index));
+
+        match matching {
+            Some(new) if new == old => {}
+            Some(new) => changes.push(StepIngredientChange {
+                ingredient_id: old.ingredient_id,
+                change: ChangeKind::Changed,
+                old: Some(old.clone()),
+                new: Some(new.clone()),
+            }),
+            None => changes.push(StepIngredientChange {
+                ingredient_id: old.ingredient_id,
+                change: ChangeKind::Removed,
+                old: Some(old.clone()),
+                new: None,
+            }),
+        }
+    }
+
+    changes.extend(unmatched.into_iter().map(|new| StepIngredientChange {
+        ingredient_id: new.ingredient_id,
+        change: ChangeKind::Added,
+        old: None,
+        new: Some(new.clone()),
+    }));
+
+    changes
+}
+
+pub fn diff_snapshots(old: &RecipeSnapshot, new: &RecipeSnapshot) -> RecipeDiff {
+    let fields = [
+        field_change("name", Some(old.name.clone()), Some(new.name.clone())),
+        field_change("defaultServings", Some(old.default_servings), Some(new.default_servings)),
+        field_change("description", old.description.clone(), new.description.clone()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let steps = (0..old.steps.len().max(new.steps.len()))
+        .filter_map(|index| {
+            let old_step = old.steps.get(index);
+            let new_step = new.steps.get(index);
+
+            let change = match (old_step, new_step) {
+                (Some(_), Some(_)) => ChangeKind::Changed,
+                (Some(_), None) => ChangeKind::Removed,
+                _ => ChangeKind::Added,
+            };
+
+            let fields = step_fields(old_step, new_step);
+            let ingredients = step_ingredient_changes(
+                old_step.map(|step| step.ingredients.as_slice()).unwrap_or_default(),
+                new_step.map(|step| step.ingredients.as_slice()).unwrap_or_default(),
+            );
+
+            if change == ChangeKind::Changed && fields.is_empty() && ingredients.is_empty() {
+                return None;
+            }
+
+            Some(StepChange {
+                index: index as i32,
+                change,
+                fields,
+                ingredients,
+            })
+        })
+        .collect();
+
+    RecipeDiff {
+        fields,
+        added_tag_ids: new
+            .tag_ids
+            .iter()
+            .filter(|id| !old.tag_ids.contains(id))
+            .copied()
+            .collect(),
+        removed_tag_ids: old
+            .tag_ids
+            .iter()
+            .filter(|id| !new.tag_ids.contains(id))
+            .copied()
+            .collect(),
+        steps,
+    }
+}
@@ -3,17 +3,22 @@ use std::fs::File;
 use std::io::copy;
 
 use async_graphql::*;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+use entity::ingredient_allergens::Allergen;
+use entity::ingredients::Diet;
+use entity::recipes::Visibility;
 use image::imageops;
 use image::GenericImageView;
 use migration::Order;
 use migration::{Alias, DynIden};
 use sea_orm::entity::prelude::*;
-use sea_orm::sea_query::{Expr, Func, Query};
+use sea_orm::sea_query::{Expr, Func, NullOrdering, Query, SelectStatement, SimpleExpr};
 use sea_orm::ActiveValue::Set;
 use sea_orm::DatabaseTransaction;
 use sea_orm::QueryOrder;
-use sea_orm::{Condition, DatabaseConnection, DbErr, JoinType, QuerySelect, TransactionTrait, Unchanged};
+use sea_orm::{
+    Condition, DatabaseConnection, DbErr, FromQueryResult, JoinType, QueryResult, QuerySelect, TransactionTrait, Unchanged,
+};
 
 use crate::utils::{correct_orientation, get_extension_from_filename, get_orientation, image_base_path, read_exif};
 
@@ -22,6 +27,7 @@ pub async fn list_recipes(
     offset: u64,
     search: Option<Vec<String>>,
     tags: Option<Vec<String>>,
+    options: &RecipeListOptions,
     db: &DatabaseConnection,
 ) -> Result<Vec<entity::recipes::Model>, DbErr> {
     let mut query = entity::recipes::Entity::find().limit(limit).offset(offset);
@@ -83,39 +89,66 @@ pub async fn list_recipes(
     }
 
     if let Some(tags) = tags {
-        for tag in tags {
-            query = query.filter(
-                Expr::col(entity::recipes::Column::Id).in_subquery(
-                    Query::select()
-                        .column(entity::recipes_tags::Column::RecipeId)
-                        .from(entity::recipes_tags::Entity)
-                        .join(
-                            JoinType::InnerJoin,
-                            entity::tags::Entity,
-                            Condition::all().add(
-                                Expr::col(entity::tags::Column::Id)
-                                    .eq(Expr::col((
-                                        entity::recipes_tags::Entity,
-                                        entity::recipes_tags::Column::TagId,
-                                    )))
-                                    .and(Expr::col((entity::tags::Entity, entity::tags::Column::Name)).eq(tag)),
-                            ),
-                        )
-                        .to_owned(),
-                ),
-            )
-        }
+        query = filter_all_tags(query, tags);
+    }
+
+    let query = filter_dietary(filter_cook_log(query, options), options.dietary.as_ref());
+
+    order_recipes(filter_visible(query, options.user_id), options)
+        .all(db)
+        .await
+}
+
+/// Restricts the query to the recipes listed for the user: public ones and the user's own ones. Unlisted and private
+/// recipes of other users are left out, for root users as well.
+pub fn filter_visible(query: Select<entity::recipes::Entity>, user_id: Option<i64>) -> Select<entity::recipes::Entity> {
+    let mut cond = Condition::any().add(entity::recipes::Column::Visibility.eq(Visibility::Public));
+
+    if let Some(user_id) = user_id {
+        cond = cond.add(entity::recipes::Column::OwnerId.eq(user_id));
+    }
+
+    query.filter(cond)
+}
+
+/// Restricts the query to recipes having *all* of the given tags
+fn filter_all_tags(mut query: Select<entity::recipes::Entity>, tags: Vec<String>) -> Select<entity::recipes::Entity> {
+    for tag in tags {
+        query = query.filter(
+            Expr::col(entity::recipes::Column::Id).in_subquery(
+                Query::select()
+                    .column(entity::recipes_tags::Column::RecipeId)
+                    .from(entity::recipes_tags::Entity)
+                    .join(
+                        JoinType::InnerJoin,
+                        entity::tags::Entity,
+                        Condition::all().add(
+                            Expr::col(entity::tags::Column::Id)
+                                .eq(Expr::col((
+                                    entity::recipes_tags::Entity,
+                                    entity::recipes_tags::Column::TagId,
+                                )))
+                                .and(Expr::col((entity::tags::Entity, entity::tags::Column::Name)).eq(tag)),
+                        ),
+                    )
+                    .to_owned(),
+            ),
+        )
     }
 
-    query.order_by_asc(entity::recipes::Column::Name).all(db).await
+    query
 }
 
 pub async fn count_recipes(
     search: Option<Vec<String>>,
     tags: Option<Vec<String>>,
+    options: &RecipeListOptions,
     db: &DatabaseConnection,
 ) -> Result<u64, DbErr> {
-    let mut query = entity::recipes::Entity::find();
+    let mut query = filter_dietary(
+        filter_cook_log(filter_visible(entity::recipes::Entity::find(), options.user_id), options),
+        options.dietary.as_ref(),
+    );
 
     if let Some(search) = search {
         let mut cond = Condition::all();
@@ -195,12 +228,416 @@ pub async fn count_recipes(
     query.count(db).await
 }
 
+#[derive(Enum, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum RecipeOrder {
+    #[default]
+    Name,
+    /// the current user's average rating
+    AverageRating,
+    /// the last time the current user cooked the recipe
+    LastCookedAt,
+    /// how often the current user cooked the recipe
+    TimesCooked,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Filters on the current user's cook log
+#[derive(InputObject, Clone, Debug, Default)]
+pub struct CookLogFilter {
+    /// only recipes rated at least this on average
+    pub min_rating: Option<f64>,
+    /// only recipes not cooked since this day (or never)
+    pub not_cooked_since: Option<NaiveDate>,
+    /// only recipes cooked at least once (`true`) or never cooked (`false`)
+    pub cooked: Option<bool>,
+}
+
+/// Dietary requirements derived from the ingredients of a recipe
+#[derive(InputObject, Clone, Debug, Default)]
+pub struct DietaryFilter {
+    /// all ingredients have to be suitable for each of these diets
+    #[graphql(default)]
+    pub diets: Vec<Diet>,
+    /// none of the ingredients may contain one of these allergens
+    #[graphql(default)]
+    pub exclude_allergens: Vec<Allergen>,
+}
+
+/// Ordering, cook log, dietary and visibility options of a recipe list. Ordering by rating or cooking history and the
+/// cook log filter refer to the cook log of `user_id`; without a user the cook log filter is ignored and recipes are
+/// ordered by name. The dietary filter doesn't depend on the user. Only the recipes visible to `user_id` are listed,
+/// see [`filter_visible`].
+#[derive(Clone, Debug, Default)]
+pub struct RecipeListOptions {
+    pub order: RecipeOrder,
+    /// defaults to ascending for the name and descending for everything else
+    pub direction: Option<SortDirection>,
+    pub cook_log: Option<CookLogFilter>,
+    pub dietary: Option<DietaryFilter>,
+    pub user_id: Option<i64>,
+}
+
+fn average_rating(user_id: i64) -> SimpleExpr {
+    Expr::cust_with_values(
+        "(SELECT AVG(cook_log.rating)::double precision FROM cook_log WHERE cook_log.recipe_id = recipes.id AND \
+         cook_log.user_id = $1)",
+        [user_id],
+    )
+}
+
+fn last_cooked_at(user_id: i64) -> SimpleExpr {
+    Expr::cust_with_values(
+        "(SELECT MAX(cook_log.date) FROM cook_log WHERE cook_log.recipe_id = recipes.id AND cook_log.user_id = $1)",
+        [user_id],
+    )
+}
+
+fn times_cooked(user_id: i64) -> SimpleExpr {
+    Expr::cust_with_values(
+        "(SELECT COUNT(*) FROM cook_log WHERE cook_log.recipe_id = recipes.id AND cook_log.user_id = $1)",
+        [user_id],
+    )
+}
+
+fn filter_cook_log(
+    mut query: Select<entity::recipes::Entity>,
+    options: &RecipeListOptions,
+) -> Select<entity::recipes::Entity> {
+    let (Some(filter), Some(user_id)) = (&options.cook_log, options.user_id) else {
+        return query;
+    };
+
+    if let Some(min_rating) = filter.min_rating {
+        query = query.filter(Expr::expr(average_rating(user_id)).gte(min_rating));
+    }
+
+    if let Some(date) = filter.not_cooked_since {
+        query = query.filter(
+            Condition::any()
+                .add(Expr::expr(last_cooked_at(user_id)).is_null())
+                .add(Expr::expr(last_cooked_at(user_id)).lt(date)),
+        );
+    }
+
+    match filter.cooked {
+        Some(true) => query.filter(Expr::expr(times_cooked(user_id)).gt(0)),
+        Some(false) => query.filter(Expr::expr(times_cooked(user_id)).eq(0)),
+        None => query,
+    }
+}
+
+/// Ids of the recipes having an ingredient matching `cond`
+fn recipes_with_ingredient(cond: Condition) -> SelectStatement {
+    Query::select()
+        .column((entity::steps::Entity, entity::steps::Column::RecipeId))
+        .from(entity::steps::Entity)
+        .join(
+            JoinType::InnerJoin,
+            entity::steps_ingredients::Entity,
+            Expr::col((
+                entity::steps_ingredients::Entity,
+                entity::steps_ingredients::Column::StepId,
+            ))
+            .equals((entity::steps::Entity, entity::steps::Column::Id)),
+        )
+        .join(
+            JoinType::InnerJoin,
+            entity::ingredients::Entity,
+            Expr::col((entity::ingredients::Entity, entity::ingredients::Column::Id)).equals((
+                entity::steps_ingredients::Entity,
+                entity::steps_ingredients::Column::IngredientId,
+            )),
+        )
+        .cond_where(cond)
+        .to_owned()
+}
+
+/// Excludes recipes with an ingredient not suitable for one of the diets or containing one of the allergens
+pub fn filter_dietary(
+    mut query: Select<entity::recipes::Entity>,
+    filter: Option<&DietaryFilter>,
+) -> Select<entity::recipes::Entity> {
+    let Some(filter) = filter else {
+        return query;
+    };
+
+    for diet in &filter.diets {
+        query = query.filter(
+            Expr::col((entity::recipes::Entity, entity::recipes::Column::Id)).not_in_subquery(
+                recipes_with_ingredient(
+                    Condition::all().add(Expr::col((entity::ingredients::Entity, diet.column())).eq(false)),
+                ),
+            ),
+        );
+    }
+
+    if !filter.exclude_allergens.is_empty() {
+        let mut subquery = recipes_with_ingredient(
+            Condition::all().add(
+                Expr::col((
+                    entity::ingredient_allergens::Entity,
+                    entity::ingredient_allergens::Column::Allergen,
+                ))
+                .is_in(filter.exclude_allergens.clone()),
+            ),
+        );
+
+        subquery.join(
+            JoinType::InnerJoin,
+            entity::ingredient_allergens::Entity,
+            Expr::col((
+                entity::ingredient_allergens::Entity,
+                entity::ingredient_allergens::Column::IngredientId,
+            ))
+            .equals((entity::ingredients::Entity, entity::ingredients::Column::Id)),
+        );
+
+        query = query.filter(
+            Expr::col((entity::recipes::Entity, entity::recipes::Column::Id)).not_in_subquery(subquery),
+        );
+    }
+
+    query
+}
+
+fn order_recipes(
+    query: Select<entity::recipes::Entity>,
+    options: &RecipeListOptions,
+) -> Select<entity::recipes::Entity> {
+    let direction = |default| match options.direction.unwrap_or(default) {
+        SortDirection::Asc => Order::Asc,
+        SortDirection::Desc => Order::Desc,
+    };
+
+    let expr = match (options.order, options.user_id) {
+        (RecipeOrder::AverageRating, Some(user_id)) => Some(average_rating(user_id)),
+        (RecipeOrder::LastCookedAt, Some(user_id)) => Some(last_cooked_at(user_id)),
+        (RecipeOrder::TimesCooked, Some(user_id)) => Some(times_cooked(user_id)),
+        _ => None,
+    };
+
+    match expr {
+        Some(expr) => query
+            .order_by_with_nulls(expr, direction(SortDirection::Desc), NullOrdering::Last)
+            .order_by_asc(entity::recipes::Column::Name),
+        None => query.order_by(entity::recipes::Column::Name, direction(SortDirection::Asc)),
+    }
+}
+
+#[derive(Enum, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum SearchMode {
+    /// substring match on the recipe name and its ingredient names
+    #[default]
+    Simple,
+    /// PostgreSQL full text search over name, description, steps and ingredients, ordered by rank
+    Fulltext,
+}
+
+#[derive(SimpleObject, Debug)]
+pub struct RecipeSearchHit {
+    pub recipe: entity::recipes::Model,
+    pub rank: f32,
+    /// name and description excerpt with the matching words wrapped in `<b>` tags
+    pub snippet: Option<String>,
+}
+
+impl FromQueryResult for RecipeSearchHit {
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        Ok(Self {
+            recipe: entity::recipes::Model::from_query_result(res, pre)?,
+            rank: res.try_get(pre, "rank")?,
+            snippet: res.try_get(pre, "snippet")?,
+        })
+    }
+}
+
+// the text search configuration has to match the one used for `recipes.search_vector` in the migration
+fn fulltext_match(search: &str) -> SimpleExpr {
+    Expr::cust_with_values("recipes.search_vector @@ websearch_to_tsquery('german', $1)", [search])
+}
+
+fn fulltext_rank(search: &str) -> SimpleExpr {
+    Expr::cust_with_values("ts_rank(recipes.search_vector, websearch_to_tsquery('german', $1))", [search])
+}
+
+fn fulltext_query(
+    search: &str,
+    tags: Option<Vec<String>>,
+    options: &RecipeListOptions,
+) -> Select<entity::recipes::Entity> {
+    let mut query = filter_visible(entity::recipes::Entity::find(), options.user_id).filter(fulltext_match(search));
+
+    if let Some(tags) = tags {
+        query = filter_all_tags(query, tags);
+    }
+
+    filter_dietary(filter_cook_log(query, options), options.dietary.as_ref())
+}
+
+/// Full text search, ordered by rank. Only the cook log, dietary and visibility filters of `options` are applied, not
+/// its order.
+pub async fn search_recipes(
+    limit: u64,
+    offset: u64,
+    search: &str,
+    tags: Option<Vec<String>>,
+    options: &RecipeListOptions,
+    db: &DatabaseConnection,
+) -> Result<Vec<RecipeSearchHit>, DbErr> {
+    fulltext_query(search, tags, options)
+        .column_as(fulltext_rank(search), "rank")
+        .column_as(
+            Expr::cust_with_values(
+                "ts_headline('german', concat_ws(' – ', recipes.name, recipes.description), \
+                 websearch_to_tsquery('german', $1), 'MaxFragments=2, MaxWords=20, MinWords=5')",
+                [search],
+            ),
+            "snippet",
+        )
+        .order_by(fulltext_rank(search), Order::Desc)
+        .order_by_asc(entity::recipes::Column::Name)
+        .limit(limit)
+        .offset(offset)
+        .into_model::<RecipeSearchHit>()
+        .all(db)
+        .await
+}
+
+pub async fn count_search_recipes(
+    search: &str,
+    tags: Option<Vec<String>>,
+    options: &RecipeListOptions,
+    db: &DatabaseConnection,
+) -> Result<u64, DbErr> {
+    fulltext_query(search, tags, options).count(db).await
+}
+
+#[derive(SimpleObject, Debug)]
+pub struct RecipeIngredientsMatch {
+    pub recipe: entity::recipes::Model,
+    /// number of the recipe's ingredients in the given set
+    pub matching: i64,
+    /// number of distinct ingredients of the recipe
+    pub total: i64,
+    /// the recipe's ingredients not in the given set
+    pub missing_ingredients: Vec<entity::ingredients::Model>,
+}
+
+struct RecipeIngredientsCount {
+    recipe: entity::recipes::Model,
+    matching: i64,
+    total: i64,
+}
+
+impl FromQueryResult for RecipeIngredientsCount {
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        Ok(Self {
+            recipe: entity::recipes::Model::from_query_result(res, pre)?,
+            matching: res.try_get(pre, "matching")?,
+            total: res.try_get(pre, "total")?,
+        })
+    }
+}
+
+#[derive(FromQueryResult)]
+struct RecipeIngredient {
+    recipe_id: i64,
+    ingredient_id: i64,
+}
+
+/// Recipes having at most `max_missing` ingredients which are not in `ingredient_ids`, the ones missing the fewest
+/// ingredients first. Recipes without any of the ingredients are left out.
+pub async fn recipes_by_ingredients(
+    ingredient_ids: &[i64],
+    max_missing: u64,
+    limit: u64,
+    offset: u64,
+    user_id: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<Vec<RecipeIngredientsMatch>, DbErr> {
+    let ingredient_id =
+        || Expr::col((entity::steps_ingredients::Entity, entity::steps_ingredients::Column::IngredientId));
+
+    let total: SimpleExpr = Func::count_distinct(ingredient_id()).into();
+    let matching: SimpleExpr =
+        Func::count_distinct(Expr::case(ingredient_id().is_in(ingredient_ids.to_vec()), ingredient_id())).into();
+
+    let missing_count = Expr::expr(total.clone()).sub(matching.clone());
+
+    let counts = filter_visible(entity::recipes::Entity::find(), user_id)
+        .join(JoinType::InnerJoin, entity::recipes::Relation::Steps.def())
+        .join(JoinType::InnerJoin, entity::steps::Relation::StepsIngridients.def())
+        .column_as(matching.clone(), "matching")
+        .column_as(total.clone(), "total")
+        .group_by(entity::recipes::Column::Id)
+        .having(Expr::expr(matching.clone()).gt(0))
+        .having(Expr::expr(missing_count.clone()).lte(max_missing))
+        .order_by(missing_count, Order::Asc)
+        .order_by(matching, Order::Desc)
+        .order_by_asc(entity::recipes::Column::Name)
+        .limit(limit)
+        .offset(offset)
+        .into_model::<RecipeIngredientsCount>()
+        .all(db)
+        .await?;
+
+    let recipe_ids = counts.iter().map(|count| count.recipe.id).collect::<Vec<_>>();
+
+    let missing = entity::steps_ingredients::Entity::find()
+        .join(JoinType::InnerJoin, entity::steps_ingredients::Relation::Steps.def())
+        .select_only()
+        .column(entity::steps::Column::RecipeId)
+        .column(entity::steps_ingredients::Column::IngredientId)
+        .distinct()
+        .filter(entity::steps::Column::RecipeId.is_in(recipe_ids))
+        .filter(entity::steps_ingredients::Column::IngredientId.is_not_in(ingredient_ids.to_vec()))
+        .into_model::<RecipeIngredient>()
+        .all(db)
+        .await?;
+
+    let ingredients = entity::ingredients::Entity::find()
+        .filter(entity::ingredients::Column::Id.is_in(missing.iter().map(|row| row.ingredient_id)))
+        .order_by_asc(entity::ingredients::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(counts
+        .into_iter()
+        .map(|count| {
+            let missing_ingredients = ingredients
+                .iter()
+                .filter(|ingredient| {
+                    missing
+                        .iter()
+                        .any(|row| row.recipe_id == count.recipe.id && row.ingredient_id == ingredient.id)
+                })
+                .cloned()
+                .collect();
+
+            RecipeIngredientsMatch {
+                recipe: count.recipe,
+                matching: count.matching,
+                total: count.total,
+                missing_ingredients,
+            }
+        })
+        .collect())
+}
+
 pub async fn get_recipe_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<entity::recipes::Model>, DbErr> {
     entity::recipes::Entity::find_by_id(id).one(db).await
 }
 
-pub async fn get_random_recipe(db: &DatabaseConnection) -> Result<Option<entity::recipes::Model>, DbErr> {
-    entity::recipes::Entity::find()
+pub async fn get_random_recipe(
+    user_id: Option<i64>,
+    db: &DatabaseConnection,
+) -> Result<Option<entity::recipes::Model>, DbErr> {
+    filter_visible(entity::recipes::Entity::find(), user_id)
         .order_by(Expr::cust("RANDOM()"), Order::Asc)
         .one(db)
         .await
@@ -209,9 +646,11 @@ pub async fn get_random_recipe(db: &DatabaseConnection) -> Result<Option<entity:
 pub async fn get_random_recipes(
     limit: u64,
     tags: Vec<String>,
+    dietary: Option<&DietaryFilter>,
+    user_id: Option<i64>,
     db: &DatabaseConnection,
 ) -> Result<Vec<entity::recipes::Model>, DbErr> {
-    let mut query = entity::recipes::Entity::find()
+    let mut query = filter_dietary(filter_visible(entity::recipes::Entity::find(), user_id), dietary)
         .order_by(Expr::cust("RANDOM()"), Order::Asc)
         .limit(limit);
 
@@ -247,6 +686,8 @@ pub struct RecipeInput {
     #[graphql(validator(max_items = 5))]
     pub tags: Option<Vec<i64>>,
     pub fitting_recipes: Option<Vec<i64>>,
+    /// defaults to public for new recipes, the current visibility is kept on updates when empty
+    pub visibility: Option<Visibility>,
 }
 
 pub async fn create_recipe(
@@ -264,6 +705,7 @@ pub async fn create_recipe(
         inserted_at: Set(now),
         updated_at: Set(now),
         owner_id: Set(Some(owner_id)),
+        visibility: Set(recipe_values.visibility.unwrap_or_default()),
         ..Default::default()
     };
 
@@ -323,12 +765,18 @@ pub async fn update_recipe(
         ..Default::default()
     };
 
+    if let Some(visibility) = values.visibility {
+        recipe.visibility = Set(visibility);
+    }
+
     if let Some(ref picture) = picture {
         recipe.image = Set(Some(picture.filename.clone()));
     }
 
     db.transaction::<_, entity::recipes::Model, DbErr>(|txn| {
         Box::pin(async move {
+            crate::recipe_revisions::record_revision(id, txn).await?;
+
             let recipe = recipe.update(txn).await?;
 
             update_save_tags(values.tags, recipe.id, txn).await?;
@@ -437,18 +885,23 @@ fn generate_pictures(recipe: entity::recipes::Model) -> anyhow::Result<()> {
     let img = recipe.image.unwrap();
     let ext = get_extension_from_filename(&img).unwrap_or(".jpg");
 
-    let path = format!("{}/{}/original.{}", image_base_path(), recipe.id, ext);
+    generate_picture_sizes(&format!("{}/{}", image_base_path(), recipe.id), ext)
+}
+
+/// Generates the large and the square thumbnail version of `{dir}/original.{ext}`
+pub fn generate_picture_sizes(dir: &str, ext: &str) -> anyhow::Result<()> {
+    let path = format!("{}/original.{}", dir, ext);
     let exif = read_exif(&path)?;
     let orientation = get_orientation(&exif);
 
     let mut img = image::open(path).expect("Failed to open image");
     img = correct_orientation(img, orientation);
 
-    let path = format!("{}/{}/large.{}", image_base_path(), recipe.id, ext);
+    let path = format!("{}/large.{}", dir, ext);
     let new_img = img.resize(800, 600, imageops::FilterType::CatmullRom);
     new_img.save(path)?;
 
-    let path = format!("{}/{}/thumbnail.{}", image_base_path(), recipe.id, ext);
+    let path = format!("{}/thumbnail.{}", dir, ext);
     let (width, height) = img.dimensions();
     let aspect_ratio = width as f32 / height as f32;
 
@@ -471,6 +924,121 @@ fn generate_pictures(recipe: entity::recipes::Model) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Copies the recipe with its steps, ingredients, tags, fitting recipes and picture into a new recipe owned by
+/// `owner_id`, linked to the original by `forked_from_id`
+pub async fn fork_recipe(
+    recipe: entity::recipes::Model,
+    owner_id: i64,
+    db: &DatabaseConnection,
+) -> Result<entity::recipes::Model, DbErr> {
+    let now = Utc::now().naive_utc();
+
+    db.transaction::<_, entity::recipes::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let fork = entity::recipes::ActiveModel {
+                name: Set(recipe.name.clone()),
+                default_servings: Set(recipe.default_servings),
+                description: Set(recipe.description.clone()),
+                image: Set(recipe.image.clone()),
+                visibility: Set(recipe.visibility),
+                owner_id: Set(Some(owner_id)),
+                forked_from_id: Set(Some(recipe.id)),
+                inserted_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?;
+
+            for recipe_tag in recipe.find_related(entity::recipes_tags::Entity).all(txn).await? {
+                entity::recipes_tags::ActiveModel {
+                    recipe_id: Set(fork.id),
+                    tag_id: Set(recipe_tag.tag_id),
+                }
+                .insert(txn)
+                .await?;
+            }
+
+            for fitting in recipe.find_related(entity::fitting::Entity).all(txn).await? {
+                entity::fitting::ActiveModel {
+                    recipe_id: Set(fork.id),
+                    fitting_recipe_id: Set(fitting.fitting_recipe_id),
+                }
+                .insert(txn)
+                .await?;
+            }
+
+            for step in recipe.find_related(entity::steps::Entity).all(txn).await? {
+                let step_ingredients = step
+                    .find_related(entity::steps_ingredients::Entity)
+                    .order_by_asc(entity::steps_ingredients::Column::Id)
+                    .all(txn)
+                    .await?;
+
+                let new_step = entity::steps::ActiveModel {
+                    name: Set(step.name),
+                    recipe_id: Set(fork.id),
+                    position: Set(step.position),
+                    description: Set(step.description),
+                    preparation_time: Set(step.preparation_time),
+                    cooking_time: Set(step.cooking_time),
+                    inserted_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(txn)
+                .await?;
+
+                for step_ingredient in step_ingredients {
+                    entity::steps_ingredients::ActiveModel {
+                        step_id: Set(new_step.id),
+                        ingredient_id: Set(step_ingredient.ingredient_id),
+                        amount: Set(step_ingredient.amount),
+                        annotation: Set(step_ingredient.annotation),
+                        unit_id: Set(step_ingredient.unit_id),
+                        inserted_at: Set(now),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    }
+                    .insert(txn)
+                    .await?;
+                }
+            }
+
+            if fork.image.is_some() {
+                copy_pictures(recipe.id, fork.id)?;
+            }
+
+            Ok(fork)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+/// Copies all picture sizes of a recipe to another recipe; nothing is copied when the pictures are missing
+fn copy_pictures(from_id: i64, to_id: i64) -> Result<(), DbErr> {
+    let from = format!("{}/{}", image_base_path(), from_id);
+    let to = format!("{}/{}", image_base_path(), to_id);
+
+    let Ok(entries) = std::fs::read_dir(&from) else {
+        return Ok(());
+    };
+
+    std::fs::create_dir_all(&to).map_err(|e| DbErr::Custom(format!("Failed to create picture directory: {}", e)))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| DbErr::Custom(format!("Failed to read pictures: {}", e)))?;
+
+        if entry.path().is_file() {
+            std::fs::copy(entry.path(), format!("{}/{}", to, entry.file_name().to_string_lossy()))
+                .map_err(|e| DbErr::Custom(format!("Failed to copy picture: {}", e)))?;
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn delete_recipe(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
     let deleted = entity::recipes::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1;
 
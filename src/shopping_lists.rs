@@ -0,0 +1,291 @@
+use async_graphql::*;
+use chrono::{Datelike, NaiveDate, Weekday};
+use entity::users::Model as User;
+use entity::{shopping_list_items as ShoppingListItem, shopping_lists as ShoppingList};
+use sea_orm::ActiveValue::{Set, Unchanged};
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, DbErr, QueryOrder, QuerySelect, TransactionTrait};
+
+use crate::bring::{BringInfo, aggregate_weekplan_ingredients};
+
+#[derive(InputObject)]
+pub struct ShoppingListItemInput {
+    #[graphql(validator(chars_min_length = 1, chars_max_length = 255))]
+    pub name: String,
+    pub amount: Option<f64>,
+    #[graphql(validator(max_length = 255))]
+    pub note: Option<String>,
+    pub ingredient_id: Option<i64>,
+    pub unit_id: Option<i64>,
+}
+
+pub async fn list_shopping_lists(
+    limit: u64,
+    offset: u64,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<ShoppingList::Model>, DbErr> {
+    ShoppingList::Entity::find()
+        .filter(ShoppingList::Column::HouseholdId.eq(user.household_id))
+        .order_by_desc(ShoppingList::Column::Week)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await
+}
+
+pub async fn get_shopping_list_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<ShoppingList::Model>, DbErr> {
+    ShoppingList::Entity::find_by_id(id).one(db).await
+}
+
+pub async fn get_shopping_list_for_week(
+    week: &NaiveDate,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Option<ShoppingList::Model>, DbErr> {
+    ShoppingList::Entity::find()
+        .filter(ShoppingList::Column::HouseholdId.eq(user.household_id))
+        .filter(ShoppingList::Column::Week.eq(beginning_of_week(week)))
+        .one(db)
+        .await
+}
+
+pub async fn get_shopping_list_item_by_id(
+    id: i64,
+    db: &DatabaseConnection,
+) -> Result<Option<ShoppingListItem::Model>, DbErr> {
+    ShoppingListItem::Entity::find_by_id(id)
+        .filter(ShoppingListItem::Column::Removed.eq(false))
+        .one(db)
+        .await
+}
+
+fn beginning_of_week(date: &NaiveDate) -> NaiveDate {
+    NaiveDate::from_isoywd_opt(date.iso_week().year(), date.iso_week().week(), Weekday::Mon).unwrap()
+}
+
+/// Creates the shopping list for the given week from the user's weekplan. If a list for that week already exists it
+/// is regenerated instead.
+pub async fn generate_shopping_list(
+    week: NaiveDate,
+    name: Option<String>,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<ShoppingList::Model, DbErr> {
+    if let Some(list) = get_shopping_list_for_week(&week, user, db).await? {
+        return regenerate_shopping_list(list, user, db).await;
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let user_id = user.id;
+    let household_id = user.household_id;
+    let week_start = beginning_of_week(&week);
+    let weekplans = crate::weekplan::list_weekplan(&week_start, None, user, db).await?;
+    let ingredients = aggregate_weekplan_ingredients(&weekplans, db).await?;
+
+    db.transaction::<_, ShoppingList::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let list = ShoppingList::ActiveModel {
+                user_id: Set(user_id),
+                household_id: Set(household_id),
+                week: Set(week_start),
+                name: Set(name.unwrap_or_else(|| format!("KW {}", week_start.iso_week().week()))),
+                inserted_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?;
+
+            for info in ingredients {
+                generated_item(list.id, info, now).insert(txn).await?;
+            }
+
+            Ok(list)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+/// Re-runs the aggregation over the weekplan of the list's week. Generated items are updated in place (keeping their
+/// checked state), vanished ones are removed and manual items are never touched. A generated item the user has
+/// edited counts as manual and is skipped as well, just like one the user has deleted.
+pub async fn regenerate_shopping_list(
+    list: ShoppingList::Model,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<ShoppingList::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+    let weekplans = crate::weekplan::list_weekplan(&list.week, None, user, db).await?;
+    let ingredients = aggregate_weekplan_ingredients(&weekplans, db).await?;
+
+    db.transaction::<_, ShoppingList::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let existing_items = ShoppingListItem::Entity::find()
+                .filter(ShoppingListItem::Column::ShoppingListId.eq(list.id))
+                .all(txn)
+                .await?;
+
+            let (manual_items, generated_items): (Vec<_>, Vec<_>) =
+                existing_items.into_iter().partition(|item| item.manual || item.removed);
+
+            let mut kept_ids = Vec::new();
+
+            for info in ingredients {
+                let unit_id = info.unit.as_ref().map(|u| u.id);
+                let key = (Some(info.ingredient.id), unit_id);
+
+                if manual_items
+                    .iter()
+                    .any(|item| (item.ingredient_id, item.unit_id) == key)
+                {
+                    continue;
+                }
+
+                let existing = generated_items
+                    .iter()
+                    .find(|item| (item.ingredient_id, item.unit_id) == key);
+
+                let mut item = generated_item(list.id, info, now);
+
+                if let Some(existing) = existing {
+                    item.id = Unchanged(existing.id);
+                    item.checked = Unchanged(existing.checked);
+                    item.inserted_at = Unchanged(existing.inserted_at);
+                    kept_ids.push(existing.id);
+                    item.update(txn).await?;
+                } else {
+                    item.insert(txn).await?;
+                }
+            }
+
+            let obsolete_ids = generated_items
+                .iter()
+                .map(|item| item.id)
+                .filter(|id| !kept_ids.contains(id))
+                .collect::<Vec<i64>>();
+
+            ShoppingListItem::Entity::delete_many()
+                .filter(ShoppingListItem::Column::Id.is_in(obsolete_ids))
+                .exec(txn)
+                .await?;
+
+            ShoppingList::ActiveModel {
+                id: Unchanged(list.id),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .update(txn)
+            .await
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+fn generated_item(list_id: i64, info: BringInfo, now: DateTime) -> ShoppingListItem::ActiveModel {
+    let note = if info.notes.is_empty() {
+        None
+    } else {
+        Some(info.notes.join(", "))
+    };
+
+    ShoppingListItem::ActiveModel {
+        shopping_list_id: Set(list_id),
+        ingredient_id: Set(Some(info.ingredient.id)),
+        unit_id: Set(info.unit.as_ref().map(|u| u.id)),
+        name: Set(info.ingredient.name),
+        amount: Set(if info.amount > 0.0 { Some(info.amount) } else { None }),
+        note: Set(note),
+        checked: Set(false),
+        manual: Set(false),
+        removed: Set(false),
+        inserted_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+}
+
+pub async fn add_shopping_list_item(
+    list_id: i64,
+    values: ShoppingListItemInput,
+    db: &DatabaseConnection,
+) -> Result<ShoppingListItem::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+
+    ShoppingListItem::ActiveModel {
+        shopping_list_id: Set(list_id),
+        ingredient_id: Set(values.ingredient_id),
+        unit_id: Set(values.unit_id),
+        name: Set(values.name),
+        amount: Set(values.amount),
+        note: Set(values.note),
+        checked: Set(false),
+        manual: Set(true),
+        removed: Set(false),
+        inserted_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn update_shopping_list_item(
+    id: i64,
+    values: ShoppingListItemInput,
+    db: &DatabaseConnection,
+) -> Result<ShoppingListItem::Model, DbErr> {
+    ShoppingListItem::ActiveModel {
+        id: Unchanged(id),
+        ingredient_id: Set(values.ingredient_id),
+        unit_id: Set(values.unit_id),
+        name: Set(values.name),
+        amount: Set(values.amount),
+        note: Set(values.note),
+        manual: Set(true),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+pub async fn check_shopping_list_item(
+    id: i64,
+    checked: bool,
+    db: &DatabaseConnection,
+) -> Result<ShoppingListItem::Model, DbErr> {
+    ShoppingListItem::ActiveModel {
+        id: Unchanged(id),
+        checked: Set(checked),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+/// Deleting a generated item only marks it as removed, so it doesn't come back when the list is regenerated
+pub async fn delete_shopping_list_item(item: ShoppingListItem::Model, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    if item.manual {
+        let result = ShoppingListItem::Entity::delete_by_id(item.id).exec(db).await?;
+        return Ok(result.rows_affected == 1);
+    }
+
+    ShoppingListItem::ActiveModel {
+        id: Unchanged(item.id),
+        removed: Set(true),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await?;
+
+    Ok(true)
+}
+
+pub async fn delete_shopping_list(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    Ok(ShoppingList::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1)
+}
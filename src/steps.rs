@@ -5,6 +5,8 @@ use sea_orm::entity::prelude::*;
 use sea_orm::ActiveValue::Set;
 use sea_orm::{DatabaseConnection, DbErr, QueryOrder, QuerySelect, TransactionTrait, Unchanged};
 
+use crate::recipe_revisions::record_revision;
+
 pub async fn list_steps(recipe_id: i64, db: &DatabaseConnection) -> Result<Vec<entity::steps::Model>, DbErr> {
     entity::steps::Entity::find()
         .filter(entity::steps::Column::RecipeId.eq(recipe_id))
@@ -62,6 +64,7 @@ pub async fn create_step(
         ..Default::default()
     };
 
+    record_revision(recipe_id, db).await?;
     let step = step.insert(db).await?;
 
     for step_ingredient in &step_values.step_ingredients {
@@ -102,6 +105,10 @@ pub async fn update_step(
 
     db.transaction::<_, entity::steps::Model, DbErr>(|txn| {
         Box::pin(async move {
+            if let Some(existing_step) = entity::steps::Entity::find_by_id(step_id).one(txn).await? {
+                record_revision(existing_step.recipe_id, txn).await?;
+            }
+
             let step = step.update(txn).await?;
 
             let existing_ingredients = entity::steps_ingredients::Entity::find()
@@ -153,6 +160,7 @@ pub async fn update_step(
 }
 
 pub async fn delete_step(step: Model, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    record_revision(step.recipe_id, db).await?;
     Ok(step.delete(db).await?.rows_affected == 1)
 }
 
@@ -161,6 +169,8 @@ pub async fn move_step_up(step: Model, db: &DatabaseConnection) -> Result<Vec<Mo
         return Ok(vec![step]);
     }
 
+    record_revision(step.recipe_id, db).await?;
+
     let other_step = entity::steps::Entity::find()
         .filter(entity::steps::Column::RecipeId.eq(step.recipe_id))
         .filter(entity::steps::Column::Position.lt(step.position))
@@ -214,6 +224,8 @@ pub async fn move_step_down(step: Model, db: &DatabaseConnection) -> Result<Vec<
         return Ok(vec![step]);
     }
 
+    record_revision(step.recipe_id, db).await?;
+
     let mut result: Vec<Model> = vec![];
     let new_position = if let Some(other_step) = other_step {
         let active_model = entity::steps::ActiveModel {
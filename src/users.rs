@@ -1,7 +1,7 @@
 use std::fs::File;
 use std::io::copy;
 
-use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
 use argon2::password_hash::{PasswordHasher, SaltString};
 use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use async_graphql::*;
@@ -42,6 +42,14 @@ pub async fn get_user_by_id(id: i64, db: &DatabaseConnection) -> Option<users::M
     users::Entity::find_by_id(id).one(db).await.unwrap_or_default()
 }
 
+pub async fn get_user_by_calendar_token(token: &str, db: &DatabaseConnection) -> Result<Option<users::Model>, DbErr> {
+    users::Entity::find()
+        .filter(users::Column::CalendarToken.eq(token))
+        .filter(users::Column::Active.eq(true))
+        .one(db)
+        .await
+}
+
 pub async fn authenticate_user(email: String, password: String, db: &DatabaseConnection) -> Option<users::Model> {
     match get_user_by_email(email, db).await {
         Some(user) => {
@@ -121,12 +129,17 @@ pub async fn create_user(
 
     db.transaction::<_, entity::users::Model, DbErr>(|txn| {
         Box::pin(async move {
+            let household =
+                crate::households::create_personal_household(user_values.name.as_deref(), &user_values.email, txn)
+                    .await?;
+
             let user = users::ActiveModel {
                 active: Set(true),
                 email: Set(user_values.email),
                 encrypted_password: Set(password_hash),
                 name: Set(user_values.name),
                 role: Set(user_values.role),
+                household_id: Set(household.id),
                 inserted_at: Set(now),
                 updated_at: Set(now),
                 ..Default::default()
@@ -234,3 +247,27 @@ fn generate_avatars(user: entity::users::Model) -> anyhow::Result<()> {
 pub async fn delete_user(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
     Ok(entity::users::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1)
 }
+
+/// Sets a new calendar token, invalidating the previous one and thus all subscriptions of the old feed URL
+pub async fn create_calendar_token(id: i64, db: &DatabaseConnection) -> Result<users::Model, DbErr> {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+
+    set_calendar_token(id, Some(token), db).await
+}
+
+pub async fn revoke_calendar_token(id: i64, db: &DatabaseConnection) -> Result<users::Model, DbErr> {
+    set_calendar_token(id, None, db).await
+}
+
+async fn set_calendar_token(id: i64, token: Option<String>, db: &DatabaseConnection) -> Result<users::Model, DbErr> {
+    users::ActiveModel {
+        id: Unchanged(id),
+        calendar_token: Set(token),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
@@ -13,6 +13,14 @@ pub fn avatar_base_path() -> String {
     std::env::var("AVATAR_DIR").expect("env variable AVATAR_DIR not set")
 }
 
+/// The URL the server is reachable at, used to build absolute URLs. Empty if not configured, URLs stay relative
+/// then.
+pub fn public_url() -> String {
+    std::env::var("PUBLIC_URL")
+        .map(|url| url.trim_end_matches('/').to_owned())
+        .unwrap_or_default()
+}
+
 pub fn read_exif(path: &str) -> Result<Exif> {
     let file = std::fs::File::open(path)?;
     let mut bufreader = std::io::BufReader::new(&file);
@@ -34,7 +42,7 @@ pub fn correct_orientation(mut img: DynamicImage, orientation: u32) -> DynamicIm
         img = img.rotate180();
     }
 
-    if orientation % 2 == 0 {
+    if orientation.is_multiple_of(2) {
         img = img.fliph();
     }
 
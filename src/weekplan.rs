@@ -1,32 +1,75 @@
+use std::collections::{HashMap, HashSet};
+
 use async_graphql::*;
-use chrono::{Datelike, NaiveDate, Weekday};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 use entity::users::Model as User;
-use entity::weekplans as Weekplan;
+use entity::weekplans::{self as Weekplan, MealSlot};
+use entity::{weekplan_template_entries as TemplateEntry, weekplan_templates as Template};
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::{Condition, Expr, JoinType, Query};
 use sea_orm::ActiveValue::Set;
-use sea_orm::{DatabaseConnection, DbErr, QueryOrder, QuerySelect, TransactionTrait, Unchanged};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbErr, PaginatorTrait, QueryOrder, QuerySelect, TransactionTrait, Unchanged,
+};
+
+use crate::favorites::filter_favorites;
+use crate::recipes::{filter_dietary, filter_visible};
+use crate::weekplan_generator::{WeekplanOptions, WeekplanStrategy, plan_week};
 
+/// The household's weekplan ordered by date and meal slot, only the given slot's entries when `meal_slot` is given
 pub async fn list_weekplan(
     week: &NaiveDate,
+    meal_slot: Option<MealSlot>,
     user: &User,
     db: &DatabaseConnection,
 ) -> Result<Vec<Weekplan::Model>, DbErr> {
     let week_start = beginning_of_week(week);
     let week_stop = end_of_week(week);
 
+    let mut query = Weekplan::Entity::find().filter(
+        Expr::col(Weekplan::Column::Date)
+            .between(week_start, week_stop)
+            .and(Expr::col(Weekplan::Column::HouseholdId).eq(user.household_id)),
+    );
+
+    if let Some(meal_slot) = meal_slot {
+        query = query.filter(Weekplan::Column::MealSlot.eq(meal_slot));
+    }
+
+    let weekplan = query
+        .order_by_asc(Weekplan::Column::Date)
+        .order_by_asc(Weekplan::Column::Id)
+        .all(db)
+        .await?;
+
+    Ok(sort_by_meal_slot(weekplan))
+}
+
+/// All entries of the user's household between `start` and `stop`, both inclusive
+pub async fn list_weekplan_between(
+    start: NaiveDate,
+    stop: NaiveDate,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<Weekplan::Model>, DbErr> {
     let weekplan = Weekplan::Entity::find()
         .filter(
             Expr::col(Weekplan::Column::Date)
-                .between(week_start, week_stop)
-                .and(Expr::col(Weekplan::Column::UserId).eq(user.id)),
+                .between(start, stop)
+                .and(Expr::col(Weekplan::Column::HouseholdId).eq(user.household_id)),
         )
         .order_by_asc(Weekplan::Column::Date)
         .order_by_asc(Weekplan::Column::Id)
         .all(db)
         .await?;
 
-    Ok(weekplan)
+    Ok(sort_by_meal_slot(weekplan))
+}
+
+/// Orders the entries by date and by the time of day of their meal slot
+fn sort_by_meal_slot(mut weekplan: Vec<Weekplan::Model>) -> Vec<Weekplan::Model> {
+    weekplan.sort_by_key(|entry| (entry.date, entry.meal_slot as u8, entry.id));
+    weekplan
 }
 
 fn beginning_of_week(date: &NaiveDate) -> NaiveDate {
@@ -43,6 +86,7 @@ pub async fn create_weekplan_for_week(
     tags: Vec<String>,
     portions: i32,
     days: Option<Vec<u32>>,
+    options: WeekplanOptions,
     db: &DatabaseConnection,
 ) -> Result<Vec<Weekplan::Model>, DbErr> {
     let week_start = beginning_of_week(&week);
@@ -51,39 +95,103 @@ pub async fn create_weekplan_for_week(
 
     db.transaction::<_, Vec<Weekplan::Model>, DbErr>(|txn| {
         Box::pin(async move {
-            let weekplan = Weekplan::Entity::find()
+            let mut weekplan = Weekplan::Entity::find()
                 .filter(
                     Expr::col(Weekplan::Column::Date)
                         .between(week_start, week_stop)
-                        .and(Expr::col(Weekplan::Column::UserId).eq(user.id)),
+                        .and(Expr::col(Weekplan::Column::HouseholdId).eq(user.household_id)),
                 )
                 .order_by_asc(Weekplan::Column::Date)
                 .order_by_asc(Weekplan::Column::Id)
                 .all(txn)
                 .await?;
 
-            let mut date = week_start;
             let days = days.unwrap_or(vec![1, 2, 3, 4, 5, 6, 7]);
-            let q = get_random_recipe(user.id, week_start, week_stop, tags);
+            let busy_days = options.busy_days.clone().unwrap_or_default();
 
-            while date <= week_stop {
-                if weekplan.iter().any(|w| w.date == date) {
-                    date += chrono::Duration::days(1);
-                    continue;
-                }
+            for slot in options.slots() {
+                let tags = slot
+                    .tags
+                    .clone()
+                    .or_else(|| slot.slot.default_tags())
+                    .unwrap_or_else(|| tags.clone());
 
-                if !days.contains(&date.weekday().num_days_from_monday()) {
-                    date += chrono::Duration::days(1);
-                    continue;
-                }
+                let (busy_dates, mut dates): (Vec<_>, Vec<_>) = week_start
+                    .iter_days()
+                    .take_while(|date| *date <= week_stop)
+                    .filter(|date| !weekplan.iter().any(|w| w.date == *date && w.meal_slot == slot.slot))
+                    .filter(|date| days.contains(&date.weekday().num_days_from_monday()))
+                    .partition(|date| busy_days.contains(&date.weekday().num_days_from_monday()));
+
+                // meals already planned count as cooking days unless they are leftovers or have leftovers
+                let cooking_dates = weekplan
+                    .iter()
+                    .filter(|w| w.meal_slot == slot.slot && w.leftovers_of_id.is_none())
+                    .filter(|w| !weekplan.iter().any(|leftovers| leftovers.leftovers_of_id == Some(w.id)))
+                    .map(|w| w.date)
+                    .chain(dates.iter().copied())
+                    .collect::<Vec<_>>();
+
+                // busy days without an earlier day to cook for them are planned like any other day
+                let leftovers = pair_leftovers(&busy_dates, &cooking_dates);
+                dates.extend(
+                    busy_dates
+                        .iter()
+                        .filter(|date| !leftovers.iter().any(|(busy_date, _)| busy_date == *date)),
+                );
+                dates.sort();
+
+                let picks = match options.strategy {
+                    WeekplanStrategy::Random => {
+                        let mut q = filter_dietary(
+                            get_random_recipe(user.id, user.household_id, week_start, week_stop, tags),
+                            options.dietary.as_ref(),
+                        );
 
-                let recipe = q.clone().one(txn).await?;
+                        if options.favorites_only {
+                            q = filter_favorites(q, user.id);
+                        }
+
+                        let mut picks: Vec<(NaiveDate, i64)> = vec![];
+
+                        for date in dates {
+                            // the picks are inserted afterwards, so they are not yet excluded by the query
+                            let picked = picks.iter().map(|(_, recipe_id)| *recipe_id).collect::<Vec<_>>();
+                            let recipe = q
+                                .clone()
+                                .filter(entity::recipes::Column::Id.is_not_in(picked))
+                                .one(txn)
+                                .await?;
+
+                            if let Some(recipe) = recipe {
+                                picks.push((date, recipe.id));
+                            }
+                        }
+
+                        picks
+                    }
+                    WeekplanStrategy::Scored => {
+                        let mut candidates = filter_dietary(
+                            candidate_recipes(user.id, user.household_id, week_start, week_stop, tags.clone()),
+                            options.dietary.as_ref(),
+                        );
+
+                        if options.favorites_only {
+                            candidates = filter_favorites(candidates, user.id);
+                        }
+
+                        plan_week(candidates, &tags, &user, &dates, &weekplan, &options, txn).await?
+                    }
+                };
 
-                if let Some(recipe) = recipe {
-                    Weekplan::ActiveModel {
+                // inserted per slot, so the next slots neither repeat these recipes nor exceed the tag limits
+                for (date, recipe_id) in picks {
+                    let entry = Weekplan::ActiveModel {
                         date: Set(date),
+                        meal_slot: Set(slot.slot),
                         user_id: Set(user.id),
-                        recipe_id: Set(recipe.id),
+                        household_id: Set(user.household_id),
+                        recipe_id: Set(recipe_id),
                         portions: Set(portions),
                         inserted_at: Set(now),
                         updated_at: Set(now),
@@ -91,42 +199,92 @@ pub async fn create_weekplan_for_week(
                     }
                     .insert(txn)
                     .await?;
+
+                    weekplan.push(entry);
                 }
 
-                date += chrono::Duration::days(1);
+                for (date, cooking_date) in leftovers {
+                    // nothing might have been found for the cooking day
+                    let Some(pos) = weekplan.iter().position(|w| {
+                        w.date == cooking_date && w.meal_slot == slot.slot && w.leftovers_of_id.is_none()
+                    }) else {
+                        continue;
+                    };
+
+                    let entry = Weekplan::ActiveModel {
+                        date: Set(date),
+                        meal_slot: Set(slot.slot),
+                        user_id: Set(user.id),
+                        household_id: Set(user.household_id),
+                        recipe_id: Set(weekplan[pos].recipe_id),
+                        portions: Set(portions),
+                        inserted_at: Set(now),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    }
+                    .insert(txn)
+                    .await?;
+
+                    let entry = link_leftovers(&entry, Some(weekplan[pos].id), txn).await?;
+
+                    weekplan[pos].portions += entry.portions;
+                    weekplan.push(entry);
+                }
             }
 
-            Weekplan::Entity::find()
-                .filter(
-                    Expr::col(Weekplan::Column::Date)
-                        .between(week_start, week_stop)
-                        .and(Expr::col(Weekplan::Column::UserId).eq(user.id)),
-                )
-                .order_by_asc(Weekplan::Column::Date)
-                .order_by_asc(Weekplan::Column::Id)
-                .all(txn)
-                .await
+            Ok(sort_by_meal_slot(weekplan))
         })
     })
     .await
     .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
 }
 
+/// Pairs each busy day with the day its meal is cooked on: the latest earlier day not yet cooked for twice. Busy days
+/// without such a day are left out.
+fn pair_leftovers(busy_dates: &[NaiveDate], cooking_dates: &[NaiveDate]) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut cooking_dates = cooking_dates.to_vec();
+    cooking_dates.sort();
+
+    let mut pairs = vec![];
+
+    for date in busy_dates {
+        if let Some(pos) = cooking_dates.iter().rposition(|cooking_date| cooking_date < date) {
+            pairs.push((*date, cooking_dates.remove(pos)));
+        }
+    }
+
+    pairs
+}
+
 pub fn get_random_recipe(
     user_id: i64,
+    household_id: i64,
     week_start: NaiveDate,
     week_stop: NaiveDate,
     tags: Vec<String>,
 ) -> Select<entity::recipes::Entity> {
-    entity::recipes::Entity::find()
+    candidate_recipes(user_id, household_id, week_start, week_stop, tags)
+        .limit(1)
+        .order_by_asc(Expr::cust("RANDOM()"))
+}
+
+/// Recipes visible to the user with one of the tags which are not yet planned in the household's week
+pub fn candidate_recipes(
+    user_id: i64,
+    household_id: i64,
+    week_start: NaiveDate,
+    week_stop: NaiveDate,
+    tags: Vec<String>,
+) -> Select<entity::recipes::Entity> {
+    filter_visible(entity::recipes::Entity::find(), Some(user_id))
         .filter(
             Expr::col(entity::recipes::Column::Id).not_in_subquery(
                 Query::select()
                     .column(Weekplan::Column::RecipeId)
                     .from(Weekplan::Entity)
                     .and_where(
-                        Expr::col(Weekplan::Column::UserId)
-                            .eq(user_id)
+                        Expr::col(Weekplan::Column::HouseholdId)
+                            .eq(household_id)
                             .and(Expr::col(Weekplan::Column::Date).between(week_start, week_stop)),
                     )
                     .to_owned(),
@@ -149,17 +307,24 @@ pub fn get_random_recipe(
                     .to_owned(),
             ),
         )
-        .limit(1)
-        .order_by_asc(Expr::cust("RANDOM()"))
 }
 
 pub async fn get_weekplan_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<Weekplan::Model>, DbErr> {
     entity::weekplans::Entity::find_by_id(id).one(db).await
 }
 
-pub async fn delete_weekplan(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
-    let deleted = Weekplan::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1;
-    Ok(deleted)
+/// Deleting leftovers reduces the portions of the entry they were cooked with. The leftovers of a deleted entry are
+/// ordinary meals afterwards.
+pub async fn delete_weekplan(weekplan: Weekplan::Model, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    db.transaction::<_, bool, DbErr>(|txn| {
+        Box::pin(async move {
+            let weekplan = link_leftovers(&weekplan, None, txn).await?;
+            let result = Weekplan::Entity::delete_by_id(weekplan.id).exec(txn).await?;
+            Ok(result.rows_affected == 1)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
 }
 
 pub async fn replace_weekplan_recipe(
@@ -167,42 +332,479 @@ pub async fn replace_weekplan_recipe(
     tags: Vec<String>,
     db: &DatabaseConnection,
 ) -> Result<Weekplan::Model, DbErr> {
-    let now = chrono::Utc::now().naive_utc();
     let start = beginning_of_week(&weekplan.date);
     let stop = end_of_week(&weekplan.date);
 
-    let q = get_random_recipe(weekplan.user_id, start, stop, tags);
+    let q = get_random_recipe(weekplan.user_id, weekplan.household_id, start, stop, tags);
     let recipe = q.one(db).await?.ok_or(DbErr::Query(sea_orm::RuntimeErr::Internal(
         "No recipe found".to_owned(),
     )))?;
 
-    let weekplan = Weekplan::ActiveModel {
+    replace_recipe(weekplan, recipe.id, db).await
+}
+
+pub async fn replace_weekplan_recipe_with_recipe(
+    weekplan: Weekplan::Model,
+    recipe_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Weekplan::Model, DbErr> {
+    replace_recipe(weekplan, recipe_id, db).await
+}
+
+/// Replaces the recipe of the entry; leftovers of the entry are the same meal, so they follow. Replaced leftovers are
+/// an ordinary meal afterwards.
+async fn replace_recipe(
+    weekplan: Weekplan::Model,
+    recipe_id: i64,
+    db: &DatabaseConnection,
+) -> Result<Weekplan::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+
+    db.transaction::<_, Weekplan::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let weekplan = link_leftovers(&weekplan, None, txn).await?;
+
+            let weekplan = Weekplan::ActiveModel {
+                id: Unchanged(weekplan.id),
+                recipe_id: Set(recipe_id),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .update(txn)
+            .await?;
+
+            Weekplan::Entity::update_many()
+                .col_expr(Weekplan::Column::RecipeId, Expr::value(weekplan.recipe_id))
+                .col_expr(Weekplan::Column::UpdatedAt, Expr::value(now))
+                .filter(Weekplan::Column::LeftoversOfId.eq(weekplan.id))
+                .exec(txn)
+                .await?;
+
+            Ok(weekplan)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+/// Links the entry to the entry it is the leftovers of, or unlinks it with `None`. Leftovers are cooked together with
+/// the entry they are linked to, so their portions are moved from the previously linked entry to the new one.
+async fn link_leftovers<C: ConnectionTrait>(
+    weekplan: &Weekplan::Model,
+    cooked_id: Option<i64>,
+    db: &C,
+) -> Result<Weekplan::Model, DbErr> {
+    if weekplan.leftovers_of_id == cooked_id {
+        return Ok(weekplan.clone());
+    }
+
+    if let Some(id) = weekplan.leftovers_of_id {
+        add_portions(id, -weekplan.portions, db).await?;
+    }
+
+    if let Some(id) = cooked_id {
+        add_portions(id, weekplan.portions, db).await?;
+    }
+
+    Weekplan::ActiveModel {
         id: Unchanged(weekplan.id),
-        recipe_id: Set(recipe.id),
-        updated_at: Set(now),
+        leftovers_of_id: Set(cooked_id),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
         ..Default::default()
     }
     .update(db)
-    .await?;
+    .await
+}
+
+async fn add_portions<C: ConnectionTrait>(id: i64, portions: i32, db: &C) -> Result<(), DbErr> {
+    Weekplan::Entity::update_many()
+        .col_expr(Weekplan::Column::Portions, Expr::cust_with_values("GREATEST(portions + $1, 1)", [portions]))
+        .filter(Weekplan::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Leftovers can only be eaten after the meal was cooked
+fn eaten_after(cooked: &Weekplan::Model, leftovers: &Weekplan::Model) -> bool {
+    (cooked.date, cooked.meal_slot as u8) < (leftovers.date, leftovers.meal_slot as u8)
+}
+
+/// Unlinks the entry from the entry it is the leftovers of and its own leftovers from it when they would be eaten
+/// before the meal is cooked, e.g. after the entry has been moved
+async fn unlink_misordered_leftovers<C: ConnectionTrait>(
+    weekplan: Weekplan::Model,
+    db: &C,
+) -> Result<Weekplan::Model, DbErr> {
+    let mut weekplan = weekplan;
+
+    if let Some(cooked_id) = weekplan.leftovers_of_id {
+        let cooked = Weekplan::Entity::find_by_id(cooked_id).one(db).await?;
+
+        if cooked.is_some_and(|cooked| !eaten_after(&cooked, &weekplan)) {
+            weekplan = link_leftovers(&weekplan, None, db).await?;
+        }
+    }
+
+    let leftovers = Weekplan::Entity::find()
+        .filter(Weekplan::Column::LeftoversOfId.eq(weekplan.id))
+        .all(db)
+        .await?;
+
+    let mut unlinked = false;
+
+    for leftovers in leftovers.iter().filter(|leftovers| !eaten_after(&weekplan, leftovers)) {
+        link_leftovers(leftovers, None, db).await?;
+        unlinked = true;
+    }
+
+    if unlinked {
+        // the portions of the unlinked leftovers have been taken off the entry
+        return Weekplan::Entity::find_by_id(weekplan.id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Weekplan not found".to_owned()));
+    }
 
     Ok(weekplan)
 }
 
-pub async fn replace_weekplan_recipe_with_recipe(
+/// The portions cooked for the entries themselves, without the portions of their leftovers
+pub(crate) async fn own_portions<C: ConnectionTrait>(
+    weekplan: &[Weekplan::Model],
+    db: &C,
+) -> Result<HashMap<i64, i32>, DbErr> {
+    let leftovers = Weekplan::Entity::find()
+        .filter(Weekplan::Column::LeftoversOfId.is_in(weekplan.iter().map(|entry| entry.id)))
+        .all(db)
+        .await?;
+
+    Ok(weekplan
+        .iter()
+        .map(|entry| {
+            let eaten_later = leftovers
+                .iter()
+                .filter(|leftovers| leftovers.leftovers_of_id == Some(entry.id))
+                .map(|leftovers| leftovers.portions)
+                .sum::<i32>();
+
+            (entry.id, (entry.portions - eaten_later).max(1))
+        })
+        .collect())
+}
+
+/// Marks the entry as the leftovers of `cooked`, it gets the recipe of the cooked entry then and its portions are
+/// cooked with that entry; without `cooked` the entry is an ordinary meal again. Leftovers of leftovers are the
+/// leftovers of the originally cooked entry.
+pub async fn set_weekplan_leftovers(
     weekplan: Weekplan::Model,
-    recipe_id: i64,
+    cooked: Option<Weekplan::Model>,
     db: &DatabaseConnection,
 ) -> Result<Weekplan::Model, DbErr> {
     let now = chrono::Utc::now().naive_utc();
 
-    let weekplan = Weekplan::ActiveModel {
-        id: Unchanged(weekplan.id),
-        recipe_id: Set(recipe_id),
+    let Some(mut cooked) = cooked else {
+        return link_leftovers(&weekplan, None, db).await;
+    };
+
+    if let Some(id) = cooked.leftovers_of_id {
+        cooked = get_weekplan_by_id(id, db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Weekplan not found".to_owned()))?;
+    }
+
+    if cooked.household_id != weekplan.household_id {
+        return Err(DbErr::RecordNotFound("Weekplan not found".to_owned()));
+    }
+
+    if !eaten_after(&cooked, &weekplan) {
+        return Err(DbErr::Custom("Leftovers have to be eaten after the meal was cooked".to_owned()));
+    }
+
+    let has_leftovers = Weekplan::Entity::find()
+        .filter(Weekplan::Column::LeftoversOfId.eq(weekplan.id))
+        .count(db)
+        .await?
+        > 0;
+
+    if has_leftovers {
+        return Err(DbErr::Custom("A meal with leftovers can't be leftovers itself".to_owned()));
+    }
+
+    db.transaction::<_, Weekplan::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let weekplan = link_leftovers(&weekplan, Some(cooked.id), txn).await?;
+
+            Weekplan::ActiveModel {
+                id: Unchanged(weekplan.id),
+                recipe_id: Set(cooked.recipe_id),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .update(txn)
+            .await
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+/// The user's weekplan templates ordered by name
+pub async fn list_weekplan_templates(user: &User, db: &DatabaseConnection) -> Result<Vec<Template::Model>, DbErr> {
+    Template::Entity::find()
+        .filter(Template::Column::UserId.eq(user.id))
+        .order_by_asc(Template::Column::Name)
+        .order_by_asc(Template::Column::Id)
+        .all(db)
+        .await
+}
+
+pub async fn get_weekplan_template_by_id(id: i64, db: &DatabaseConnection) -> Result<Option<Template::Model>, DbErr> {
+    Template::Entity::find_by_id(id).one(db).await
+}
+
+/// Saves the household's plan of the given week as a new template of the user. Templates don't know about leftovers:
+/// cooked entries are saved with their own portions, their leftovers as ordinary meals.
+pub async fn save_weekplan_template(
+    week: NaiveDate,
+    name: String,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Template::Model, DbErr> {
+    let weekplan = list_weekplan(&week, None, user, db).await?;
+    let portions = own_portions(&weekplan, db).await?;
+    let now = chrono::Utc::now().naive_utc();
+    let user_id = user.id;
+
+    db.transaction::<_, Template::Model, DbErr>(|txn| {
+        Box::pin(async move {
+            let template = Template::ActiveModel {
+                user_id: Set(user_id),
+                name: Set(name),
+                inserted_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?;
+
+            for entry in weekplan {
+                TemplateEntry::ActiveModel {
+                    template_id: Set(template.id),
+                    weekday: Set(entry.date.weekday().num_days_from_monday() as i32),
+                    meal_slot: Set(entry.meal_slot),
+                    recipe_id: Set(entry.recipe_id),
+                    portions: Set(portions[&entry.id]),
+                    ..Default::default()
+                }
+                .insert(txn)
+                .await?;
+            }
+
+            Ok(template)
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))
+}
+
+pub async fn delete_weekplan_template(id: i64, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    Ok(Template::Entity::delete_by_id(id).exec(db).await?.rows_affected == 1)
+}
+
+/// Plans the template's recipes in the household's given week. Meals already planned in the week are kept and the
+/// template's entries for them are skipped, just like recipes no longer visible to the user.
+pub async fn apply_weekplan_template(
+    template: &Template::Model,
+    week: NaiveDate,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<Weekplan::Model>, DbErr> {
+    let entries = TemplateEntry::Entity::find()
+        .filter(TemplateEntry::Column::TemplateId.eq(template.id))
+        .order_by_asc(TemplateEntry::Column::Id)
+        .all(db)
+        .await?;
+
+    let recipe_ids = entries.iter().map(|entry| entry.recipe_id).collect::<Vec<_>>();
+    let visible = filter_visible(entity::recipes::Entity::find(), Some(user.id))
+        .filter(entity::recipes::Column::Id.is_in(recipe_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|recipe| recipe.id)
+        .collect::<HashSet<_>>();
+
+    let week_start = beginning_of_week(&week);
+    let mut planned = planned_meals(&list_weekplan(&week, None, user, db).await?);
+    let now = chrono::Utc::now().naive_utc();
+
+    let new_entries = entries
+        .into_iter()
+        .filter(|entry| visible.contains(&entry.recipe_id))
+        .filter_map(|entry| {
+            let date = week_start + Duration::days(entry.weekday.into());
+            planned.insert((date, entry.meal_slot)).then(|| Weekplan::ActiveModel {
+                date: Set(date),
+                meal_slot: Set(entry.meal_slot),
+                user_id: Set(user.id),
+                household_id: Set(user.household_id),
+                recipe_id: Set(entry.recipe_id),
+                portions: Set(entry.portions),
+                inserted_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if !new_entries.is_empty() {
+        Weekplan::Entity::insert_many(new_entries).exec(db).await?;
+    }
+
+    list_weekplan(&week, None, user, db).await
+}
+
+/// Copies or moves the household's plan of one week to another week, keeping the weekdays. Meals already planned in
+/// the target week are kept; the source entries for them are skipped and, when moving, stay where they are. Copied
+/// leftovers are linked to the copy of their cooked entry; when that one isn't copied they are an ordinary meal.
+/// Moved leftovers which would be eaten before the meal is cooked are unlinked.
+pub async fn copy_weekplan_week(
+    from_week: NaiveDate,
+    to_week: NaiveDate,
+    move_entries: bool,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Vec<Weekplan::Model>, DbErr> {
+    let from_start = beginning_of_week(&from_week);
+    let to_start = beginning_of_week(&to_week);
+
+    if from_start == to_start {
+        return list_weekplan(&to_week, None, user, db).await;
+    }
+
+    let source = list_weekplan(&from_week, None, user, db).await?;
+    let portions = own_portions(&source, db).await?;
+    let mut planned = planned_meals(&list_weekplan(&to_week, None, user, db).await?);
+    let now = chrono::Utc::now().naive_utc();
+    let planner = user.clone();
+
+    db.transaction::<_, (), DbErr>(|txn| {
+        Box::pin(async move {
+            let mut moved = vec![];
+            let mut copies: HashMap<i64, i64> = HashMap::new();
+
+            // the entries are ordered by date and meal, so cooked entries are copied before their leftovers
+            for entry in source {
+                let date = to_start + (entry.date - from_start);
+
+                if !planned.insert((date, entry.meal_slot)) {
+                    continue;
+                }
+
+                if move_entries {
+                    let entry = Weekplan::ActiveModel {
+                        id: Unchanged(entry.id),
+                        date: Set(date),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    }
+                    .update(txn)
+                    .await?;
+
+                    moved.push(entry.id);
+                } else {
+                    let copy = copied_entry(&entry, date, entry.meal_slot, portions[&entry.id], &planner, now)
+                        .insert(txn)
+                        .await?;
+
+                    if let Some(cooked_id) = entry.leftovers_of_id.and_then(|id| copies.get(&id)) {
+                        link_leftovers(&copy, Some(*cooked_id), txn).await?;
+                    }
+
+                    copies.insert(entry.id, copy.id);
+                }
+            }
+
+            for id in moved {
+                // earlier unlinks might have changed the entry
+                if let Some(entry) = Weekplan::Entity::find_by_id(id).one(txn).await? {
+                    unlink_misordered_leftovers(entry, txn).await?;
+                }
+            }
+
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))))?;
+
+    list_weekplan(&to_week, None, user, db).await
+}
+
+/// Copies or moves a single entry to another day and optionally another meal. A copy is an ordinary meal with the
+/// entry's own portions. A moved entry is unlinked from leftovers which would be eaten before the meal is cooked.
+pub async fn copy_weekplan_entry(
+    entry: Weekplan::Model,
+    date: NaiveDate,
+    meal_slot: Option<MealSlot>,
+    move_entry: bool,
+    user: &User,
+    db: &DatabaseConnection,
+) -> Result<Weekplan::Model, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+    let meal_slot = meal_slot.unwrap_or(entry.meal_slot);
+
+    if move_entry {
+        return db
+            .transaction::<_, Weekplan::Model, DbErr>(|txn| {
+                Box::pin(async move {
+                    let entry = Weekplan::ActiveModel {
+                        id: Unchanged(entry.id),
+                        date: Set(date),
+                        meal_slot: Set(meal_slot),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    }
+                    .update(txn)
+                    .await?;
+
+                    unlink_misordered_leftovers(entry, txn).await
+                })
+            })
+            .await
+            .map_err(|e| DbErr::Query(sea_orm::RuntimeErr::Internal(format!("Transaction failed: {}", e))));
+    }
+
+    let portions = own_portions(std::slice::from_ref(&entry), db).await?;
+    copied_entry(&entry, date, meal_slot, portions[&entry.id], user, now)
+        .insert(db)
+        .await
+}
+
+fn copied_entry(
+    entry: &Weekplan::Model,
+    date: NaiveDate,
+    meal_slot: MealSlot,
+    portions: i32,
+    user: &User,
+    now: chrono::NaiveDateTime,
+) -> Weekplan::ActiveModel {
+    Weekplan::ActiveModel {
+        date: Set(date),
+        meal_slot: Set(meal_slot),
+        user_id: Set(user.id),
+        household_id: Set(entry.household_id),
+        recipe_id: Set(entry.recipe_id),
+        portions: Set(portions),
+        inserted_at: Set(now),
         updated_at: Set(now),
         ..Default::default()
     }
-    .update(db)
-    .await?;
+}
 
-    Ok(weekplan)
+/// The days and meals already planned
+fn planned_meals(weekplan: &[Weekplan::Model]) -> HashSet<(NaiveDate, MealSlot)> {
+    weekplan.iter().map(|entry| (entry.date, entry.meal_slot)).collect()
 }
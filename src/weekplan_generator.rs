@@ -0,0 +1,364 @@
+//! Scoring based selection of the recipes of a weekplan. Every candidate gets a score from the user's rating, the
+//! time since it was last cooked or planned and a bit of randomness; recipes cooked recently, recipes exceeding the
+//! per-tag limit or recipes not fitting the day's time constraints are skipped. When no recipe is left for a day the
+//! restrictions are relaxed one after another.
+
+use std::collections::{HashMap, HashSet};
+
+use async_graphql::*;
+use chrono::{Datelike, NaiveDate};
+use entity::users::Model as User;
+use entity::weekplans::{self as Weekplan, MealSlot};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, JoinType};
+use sea_orm::{ConnectionTrait, DbErr, FromQueryResult, QuerySelect};
+
+use crate::recipes::DietaryFilter;
+
+#[derive(Enum, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum WeekplanStrategy {
+    /// a random recipe per day, only avoiding repeats within the week
+    #[default]
+    Random,
+    /// recipes are picked by rating, cooking history, tag balance and time constraints
+    Scored,
+}
+
+#[derive(InputObject, Clone, Debug)]
+pub struct DayConstraint {
+    /// day of the week, numbered like the `days` of a weekplan (0 is Monday)
+    pub day: u32,
+    /// the maximum preparation plus cooking time in minutes
+    pub max_time: Option<i32>,
+    /// the minimum preparation plus cooking time in minutes
+    pub min_time: Option<i32>,
+}
+
+#[derive(InputObject, Clone, Debug)]
+pub struct MealSlotOptions {
+    pub slot: MealSlot,
+    /// the tags the slot's recipes are picked by; defaults to "frühstück" for breakfast, "snack" for snacks and the
+    /// tags of the week for lunch and dinner
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(InputObject, Clone, Debug, Default)]
+pub struct WeekplanOptions {
+    #[graphql(default)]
+    pub strategy: WeekplanStrategy,
+    /// skip recipes cooked or planned within this many weeks before, defaults to 3
+    pub avoid_recent_weeks: Option<u32>,
+    /// the maximum number of recipes per week sharing a tag, defaults to 2
+    pub max_per_tag: Option<u32>,
+    pub day_constraints: Option<Vec<DayConstraint>>,
+    /// applies to both strategies
+    pub dietary: Option<DietaryFilter>,
+    /// only pick from the user's favourites, applies to both strategies
+    #[graphql(default)]
+    pub favorites_only: bool,
+    /// the meals planned per day, in the order they are filled; defaults to dinner only
+    pub meal_slots: Option<Vec<MealSlotOptions>>,
+    /// days of the week without time for cooking, numbered like the `days` of a weekplan (0 is Monday); they get the
+    /// leftovers of the same meal of an earlier day, which is planned with the portions of both days
+    pub busy_days: Option<Vec<u32>>,
+}
+
+impl WeekplanOptions {
+    pub fn slots(&self) -> Vec<MealSlotOptions> {
+        self.meal_slots.clone().unwrap_or_else(|| {
+            vec![MealSlotOptions {
+                slot: MealSlot::Dinner,
+                tags: None,
+            }]
+        })
+    }
+}
+
+const DEFAULT_AVOID_RECENT_WEEKS: u32 = 3;
+const DEFAULT_MAX_PER_TAG: u32 = 2;
+
+/// Unrated recipes are treated like recipes with this rating
+const NEUTRAL_RATING: f64 = 3.0;
+
+/// After this many days a recipe counts as "not cooked for a long time"
+const FORGOTTEN_AFTER_DAYS: f64 = 84.0;
+
+#[derive(FromQueryResult)]
+struct Candidate {
+    id: i64,
+    jitter: f64,
+}
+
+#[derive(FromQueryResult)]
+struct RecipeTime {
+    recipe_id: i64,
+    total_time: i64,
+}
+
+#[derive(FromQueryResult)]
+struct RecipeTag {
+    recipe_id: i64,
+    name: String,
+}
+
+#[derive(FromQueryResult)]
+struct RecipeHistory {
+    recipe_id: i64,
+    average_rating: Option<f64>,
+    last_date: Option<Date>,
+}
+
+struct ScoredRecipe {
+    id: i64,
+    score: f64,
+    total_time: i64,
+    tags: Vec<String>,
+    last_date: Option<NaiveDate>,
+}
+
+/// The restrictions applied when picking a recipe, from strictest to most relaxed
+#[derive(Clone, Copy)]
+struct Restrictions {
+    recency: bool,
+    tag_limit: bool,
+    time: bool,
+}
+
+const RELAXATIONS: [Restrictions; 4] = [
+    Restrictions {
+        recency: true,
+        tag_limit: true,
+        time: true,
+    },
+    Restrictions {
+        recency: false,
+        tag_limit: true,
+        time: true,
+    },
+    Restrictions {
+        recency: false,
+        tag_limit: false,
+        time: true,
+    },
+    Restrictions {
+        recency: false,
+        tag_limit: false,
+        time: false,
+    },
+];
+
+/// Picks one of the `candidates` for each of the given dates. `planned` are the entries already in the week, their
+/// tags count towards the tag limit. The tags the candidates were selected by don't count, every recipe has one of
+/// them.
+pub async fn plan_week<C: ConnectionTrait>(
+    candidates: Select<entity::recipes::Entity>,
+    selection_tags: &[String],
+    user: &User,
+    dates: &[NaiveDate],
+    planned: &[Weekplan::Model],
+    options: &WeekplanOptions,
+    db: &C,
+) -> Result<Vec<(NaiveDate, i64)>, DbErr> {
+    let Some(week_start) = dates.iter().min().copied() else {
+        return Ok(vec![]);
+    };
+
+    let candidates = candidates
+        .select_only()
+        .column(entity::recipes::Column::Id)
+        .column_as(Expr::cust("RANDOM()"), "jitter")
+        .into_model::<Candidate>()
+        .all(db)
+        .await?;
+
+    let candidate_ids = candidates.iter().map(|candidate| candidate.id).collect::<Vec<_>>();
+    let planned_ids = planned.iter().map(|entry| entry.recipe_id).collect::<Vec<_>>();
+
+    let times = recipe_times(&candidate_ids, db).await?;
+    let mut recipe_tags =
+        recipe_tags(&[candidate_ids.as_slice(), planned_ids.as_slice()].concat(), selection_tags, db).await?;
+    let history = recipe_history(user, week_start, &candidate_ids, db).await?;
+
+    let mut recipes = candidates
+        .into_iter()
+        .map(|candidate| {
+            let history = history.get(&candidate.id);
+            let rating = history.and_then(|h| h.average_rating).unwrap_or(NEUTRAL_RATING);
+            let last_date = history.and_then(|h| h.last_date);
+            let days_since = last_date.map_or(FORGOTTEN_AFTER_DAYS, |date| (week_start - date).num_days() as f64);
+
+            ScoredRecipe {
+                id: candidate.id,
+                score: (rating - 1.0) / 4.0 + (days_since / FORGOTTEN_AFTER_DAYS).min(1.0) + candidate.jitter * 0.5,
+                total_time: times.get(&candidate.id).copied().unwrap_or(0),
+                tags: recipe_tags.remove(&candidate.id).unwrap_or_default(),
+                last_date,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    recipes.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let avoid_since =
+        week_start - chrono::Duration::weeks(options.avoid_recent_weeks.unwrap_or(DEFAULT_AVOID_RECENT_WEEKS) as i64);
+    let max_per_tag = options.max_per_tag.unwrap_or(DEFAULT_MAX_PER_TAG) as usize;
+
+    let mut tag_counts: HashMap<String, usize> = HashMap::new();
+    for tag in planned
+        .iter()
+        .flat_map(|entry| recipe_tags.get(&entry.recipe_id).into_iter().flatten())
+    {
+        *tag_counts.entry(tag.clone()).or_default() += 1;
+    }
+
+    let mut used = HashSet::new();
+    let mut picks = vec![];
+
+    for date in dates {
+        let constraint = options
+            .day_constraints
+            .iter()
+            .flatten()
+            .find(|constraint| constraint.day == date.weekday().num_days_from_monday());
+
+        let fits = |recipe: &ScoredRecipe, restrictions: Restrictions| {
+            if used.contains(&recipe.id) {
+                return false;
+            }
+
+            if restrictions.recency && recipe.last_date.is_some_and(|last_date| last_date >= avoid_since) {
+                return false;
+            }
+
+            if restrictions.tag_limit
+                && recipe
+                    .tags
+                    .iter()
+                    .any(|tag| tag_counts.get(tag).copied().unwrap_or(0) >= max_per_tag)
+            {
+                return false;
+            }
+
+            if let (true, Some(constraint)) = (restrictions.time, constraint) {
+                if constraint.max_time.is_some_and(|max| recipe.total_time > max as i64)
+                    || constraint.min_time.is_some_and(|min| recipe.total_time < min as i64)
+                {
+                    return false;
+                }
+            }
+
+            true
+        };
+
+        let pick = RELAXATIONS
+            .iter()
+            .find_map(|restrictions| recipes.iter().find(|recipe| fits(recipe, *restrictions)));
+
+        let Some(recipe) = pick else {
+            continue;
+        };
+
+        for tag in &recipe.tags {
+            *tag_counts.entry(tag.clone()).or_default() += 1;
+        }
+
+        used.insert(recipe.id);
+        picks.push((*date, recipe.id));
+    }
+
+    Ok(picks)
+}
+
+/// Preparation plus cooking time of all steps per recipe
+async fn recipe_times<C: ConnectionTrait>(ids: &[i64], db: &C) -> Result<HashMap<i64, i64>, DbErr> {
+    let times = entity::steps::Entity::find()
+        .select_only()
+        .column(entity::steps::Column::RecipeId)
+        .column_as(Expr::cust("SUM(steps.preparation_time + steps.cooking_time)::bigint"), "total_time")
+        .filter(entity::steps::Column::RecipeId.is_in(ids.to_vec()))
+        .group_by(entity::steps::Column::RecipeId)
+        .into_model::<RecipeTime>()
+        .all(db)
+        .await?;
+
+    Ok(times
+        .into_iter()
+        .map(|time| (time.recipe_id, time.total_time))
+        .collect())
+}
+
+/// The tags of the recipes, except for `selection_tags`
+async fn recipe_tags<C: ConnectionTrait>(
+    ids: &[i64],
+    selection_tags: &[String],
+    db: &C,
+) -> Result<HashMap<i64, Vec<String>>, DbErr> {
+    let tags = entity::recipes_tags::Entity::find()
+        .join(JoinType::InnerJoin, entity::recipes_tags::Relation::Tags.def())
+        .select_only()
+        .column(entity::recipes_tags::Column::RecipeId)
+        .column(entity::tags::Column::Name)
+        .filter(entity::recipes_tags::Column::RecipeId.is_in(ids.to_vec()))
+        .into_model::<RecipeTag>()
+        .all(db)
+        .await?;
+
+    let mut map: HashMap<i64, Vec<String>> = HashMap::new();
+    for tag in tags {
+        if !selection_tags.contains(&tag.name) {
+            map.entry(tag.recipe_id).or_default().push(tag.name);
+        }
+    }
+
+    Ok(map)
+}
+
+/// The user's average rating and the last day a recipe was cooked by the user or planned in the user's household
+/// before `before`
+async fn recipe_history<C: ConnectionTrait>(
+    user: &User,
+    before: NaiveDate,
+    ids: &[i64],
+    db: &C,
+) -> Result<HashMap<i64, RecipeHistory>, DbErr> {
+    let mut history = entity::cook_log::Entity::find()
+        .select_only()
+        .column(entity::cook_log::Column::RecipeId)
+        .column_as(Expr::cust("AVG(cook_log.rating)::double precision"), "average_rating")
+        .column_as(entity::cook_log::Column::Date.max(), "last_date")
+        .filter(entity::cook_log::Column::UserId.eq(user.id))
+        .filter(entity::cook_log::Column::RecipeId.is_in(ids.to_vec()))
+        .filter(entity::cook_log::Column::Date.lt(before))
+        .group_by(entity::cook_log::Column::RecipeId)
+        .into_model::<RecipeHistory>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|history| (history.recipe_id, history))
+        .collect::<HashMap<_, _>>();
+
+    let planned = Weekplan::Entity::find()
+        .select_only()
+        .column(Weekplan::Column::RecipeId)
+        .column_as(Expr::cust("NULL::double precision"), "average_rating")
+        .column_as(Weekplan::Column::Date.max(), "last_date")
+        .filter(Weekplan::Column::HouseholdId.eq(user.household_id))
+        .filter(Weekplan::Column::RecipeId.is_in(ids.to_vec()))
+        .filter(Weekplan::Column::Date.lt(before))
+        .group_by(Weekplan::Column::RecipeId)
+        .into_model::<RecipeHistory>()
+        .all(db)
+        .await?;
+
+    for entry in planned {
+        let history = history.entry(entry.recipe_id).or_insert(RecipeHistory {
+            recipe_id: entry.recipe_id,
+            average_rating: None,
+            last_date: None,
+        });
+
+        history.last_date = history.last_date.max(entry.last_date);
+    }
+
+    Ok(history)
+}